@@ -1,39 +1,80 @@
-CREATE DATABASE IF NOT EXISTS mydatabase;
+CREATE DATABASE IF NOT EXISTS mydatabase CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci;
 
 GRANT ALL PRIVILEGES ON mydatabase.* TO 'myuser' @'%' IDENTIFIED BY 'mypassword';
 
 USE mydatabase;
 
 CREATE TABLE IF NOT EXISTS circles (
-    id INT AUTO_INCREMENT PRIMARY KEY,
+    id VARCHAR(36) PRIMARY KEY,
     name VARCHAR(255) NOT NULL,
     capacity INT NOT NULL,
-    owner_id INT NOT NULL
-);
+    owner_id VARCHAR(36) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    deleted_at DATETIME NULL DEFAULT NULL,
+    UNIQUE KEY uq_circles_name (name)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
 
 CREATE TABLE IF NOT EXISTS members (
-    id INT AUTO_INCREMENT PRIMARY KEY,
+    id VARCHAR(36) PRIMARY KEY,
     name VARCHAR(255) NOT NULL,
     grade INT NOT NULL,
-    circle_id INT,
+    circle_id VARCHAR(36),
     age INT NOT NULL DEFAULT 20,
     major VARCHAR(255) NOT NULL DEFAULT 'other',
+    role VARCHAR(16) NOT NULL DEFAULT 'member',
+    owner_of VARCHAR(36) NULL DEFAULT NULL,
+    deleted_at DATETIME NULL DEFAULT NULL,
+    UNIQUE KEY uq_members_owner_of (owner_of),
+    KEY idx_members_circle_id_deleted_at (circle_id, deleted_at),
     FOREIGN KEY (circle_id) REFERENCES circles(id) ON DELETE CASCADE
-);
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS circles_archive (
+    id VARCHAR(36) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    capacity INT NOT NULL,
+    owner_id VARCHAR(36) NOT NULL,
+    created_at TIMESTAMP NULL DEFAULT NULL,
+    deleted_at DATETIME NOT NULL
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS members_archive (
+    id VARCHAR(36) NOT NULL,
+    circle_id VARCHAR(36) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    age INT NOT NULL,
+    grade INT NOT NULL,
+    major VARCHAR(255) NOT NULL,
+    role VARCHAR(16) NOT NULL,
+    PRIMARY KEY (circle_id, id)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS membership_history (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    member_id VARCHAR(36) NOT NULL,
+    circle_id VARCHAR(36) NOT NULL,
+    event VARCHAR(16) NOT NULL,
+    occurred_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    KEY idx_membership_history_member_id (member_id, id)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
 
 INSERT INTO
-    circles (name, capacity, owner_id)
+    circles (id, name, capacity, owner_id)
 VALUES
-    ('Circle A', 5, 1),
-    ('Circle B', 8, 2),
-    ('Circle C', 10, 3);
+    ('1', 'Circle A', 5, '1'),
+    ('2', 'Circle B', 8, '2'),
+    ('3', 'Circle C', 10, '3');
 
 INSERT INTO
-    members (name, grade, circle_id, age, major)
+    members (id, name, grade, circle_id, age, major, role, owner_of)
 VALUES
-    ('Alice', 3, 1, 21, 'math'),
-    ('Bob', 2, 2, 22, 'math'),
-    ('Charlie', 3, 3, 23, 'math'),
-    ('David', 4, 1, 21, 'math'),
-    ('Eve', 2, 2, 19, 'math'),
-    ('Frank', 4, 3, 20, 'math');
\ No newline at end of file
+    ('1', 'Alice', 3, '1', 21, 'math', 'owner', '1'),
+    ('2', 'Bob', 2, '2', 22, 'math', 'owner', '2'),
+    ('3', 'Charlie', 3, '3', 23, 'math', 'owner', '3'),
+    ('4', 'David', 4, '1', 21, 'math', 'member', NULL),
+    ('5', 'Eve', 2, '2', 19, 'math', 'member', NULL),
+    ('6', 'Frank', 4, '3', 20, 'math', 'member', NULL);
+
+INSERT INTO
+    membership_history (member_id, circle_id, event)
+SELECT id, circle_id, 'joined' FROM members;
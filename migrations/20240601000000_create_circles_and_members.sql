@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS circles (
+    id VARCHAR(36) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    capacity INT NOT NULL,
+    owner_id VARCHAR(36) NOT NULL,
+    deleted_at DATETIME NULL DEFAULT NULL,
+    UNIQUE KEY uq_circles_name (name)
+);
+
+CREATE TABLE IF NOT EXISTS members (
+    id VARCHAR(36) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    grade INT NOT NULL,
+    circle_id VARCHAR(36),
+    age INT NOT NULL DEFAULT 20,
+    major VARCHAR(255) NOT NULL DEFAULT 'other',
+    FOREIGN KEY (circle_id) REFERENCES circles(id) ON DELETE CASCADE
+);
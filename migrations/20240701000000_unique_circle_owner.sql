@@ -0,0 +1,10 @@
+-- `owner_of` holds the circle id on the owner's row and NULL on every other
+-- row, so the unique key allows at most one owner per circle.
+ALTER TABLE members
+    ADD COLUMN owner_of VARCHAR(36) NULL DEFAULT NULL,
+    ADD UNIQUE KEY uq_members_owner_of (owner_of);
+
+UPDATE members
+    JOIN circles ON circles.owner_id = members.id AND circles.id = members.circle_id
+SET
+    members.owner_of = circles.id;
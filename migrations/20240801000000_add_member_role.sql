@@ -0,0 +1,8 @@
+ALTER TABLE members
+    ADD COLUMN role VARCHAR(16) NOT NULL DEFAULT 'member';
+
+UPDATE members
+SET
+    role = 'owner'
+WHERE
+    owner_of IS NOT NULL;
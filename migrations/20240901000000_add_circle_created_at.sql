@@ -0,0 +1,4 @@
+-- TIMESTAMP is stored as UTC; the application reads it back with
+-- UNIX_TIMESTAMP, so the session timezone never leaks into responses.
+ALTER TABLE circles
+    ADD COLUMN created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP;
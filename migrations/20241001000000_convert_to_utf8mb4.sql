@@ -0,0 +1,12 @@
+-- The MariaDB image defaults to latin1, which can't store names such as
+-- 音楽部. `members.circle_id` references `circles.id`, so the key check is
+-- paused while the two tables are converted one after the other.
+SET FOREIGN_KEY_CHECKS = 0;
+
+ALTER TABLE circles
+    CONVERT TO CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci;
+
+ALTER TABLE members
+    CONVERT TO CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci;
+
+SET FOREIGN_KEY_CHECKS = 1;
@@ -0,0 +1,23 @@
+-- Copies of deleted circles and their members, written in the same
+-- transaction as the soft delete and dropped again by a restore. Ids and
+-- timestamps are kept as they were; there are no foreign keys, so the copies
+-- outlive a purge of the live rows.
+CREATE TABLE IF NOT EXISTS circles_archive (
+    id VARCHAR(36) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    capacity INT NOT NULL,
+    owner_id VARCHAR(36) NOT NULL,
+    created_at TIMESTAMP NULL DEFAULT NULL,
+    deleted_at DATETIME NOT NULL
+) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS members_archive (
+    id VARCHAR(36) PRIMARY KEY,
+    circle_id VARCHAR(36) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    age INT NOT NULL,
+    grade INT NOT NULL,
+    major VARCHAR(255) NOT NULL,
+    role VARCHAR(16) NOT NULL,
+    KEY idx_members_archive_circle_id (circle_id)
+) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci;
@@ -0,0 +1,5 @@
+-- Members dropped from a roster keep their row with `deleted_at` set, so a
+-- circle can still be read with them. Writing the member again clears it.
+ALTER TABLE members
+    ADD COLUMN deleted_at DATETIME NULL DEFAULT NULL,
+    ADD KEY idx_members_circle_id_deleted_at (circle_id, deleted_at);
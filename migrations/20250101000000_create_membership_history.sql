@@ -0,0 +1,11 @@
+-- One row each time a member joins or leaves a circle, written in the same
+-- transaction as the roster change. There are no foreign keys, so the history
+-- outlives the member and circle rows it names.
+CREATE TABLE IF NOT EXISTS membership_history (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    member_id VARCHAR(36) NOT NULL,
+    circle_id VARCHAR(36) NOT NULL,
+    event VARCHAR(16) NOT NULL,
+    occurred_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    KEY idx_membership_history_member_id (member_id, id)
+) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci;
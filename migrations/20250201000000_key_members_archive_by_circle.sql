@@ -0,0 +1,7 @@
+-- A merge moves the source's members into the target after archiving the
+-- source, so the same member id can be archived once per circle it was
+-- deleted from. The key is the pair; the old circle_id index is its prefix.
+ALTER TABLE members_archive
+    DROP PRIMARY KEY,
+    ADD PRIMARY KEY (circle_id, id),
+    DROP KEY idx_members_archive_circle_id;
@@ -1,5 +1,3 @@
+-- members are removed by the ON DELETE CASCADE foreign key.
 DELETE FROM
-    members;
-
-DELETE FROM
-    circles;
\ No newline at end of file
+    circles;
@@ -1 +1,18 @@
+use std::{env, str::FromStr};
+
+pub mod admin;
+pub mod circle;
 pub mod connect;
+#[cfg(all(test, feature = "docker-tests"))]
+mod container;
+pub mod http;
+pub mod logging;
+
+fn env_or<T: FromStr>(key: &str, default: T) -> T {
+    match env::var(key) {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("{} must be a number", key)),
+        Err(_) => default,
+    }
+}
@@ -0,0 +1,10 @@
+use std::env;
+
+use dotenv::dotenv;
+
+// Off unless ALLOW_RESET=true. Read on every call so a test can flip it
+// without rebuilding the router.
+pub fn reset_allowed() -> bool {
+    dotenv().ok();
+    env::var("ALLOW_RESET").is_ok_and(|value| value.eq_ignore_ascii_case("true"))
+}
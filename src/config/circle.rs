@@ -0,0 +1,15 @@
+use std::sync::OnceLock;
+
+use dotenv::dotenv;
+
+use super::env_or;
+
+// Capacity given to a circle created without one; read once from
+// DEFAULT_CIRCLE_CAPACITY.
+pub fn default_capacity() -> i16 {
+    static DEFAULT_CAPACITY: OnceLock<i16> = OnceLock::new();
+    *DEFAULT_CAPACITY.get_or_init(|| {
+        dotenv().ok();
+        env_or("DEFAULT_CIRCLE_CAPACITY", 10)
+    })
+}
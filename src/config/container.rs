@@ -0,0 +1,104 @@
+use std::{
+    process::Command,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use sqlx::{mysql::MySqlPoolOptions, MySqlPool};
+use tokio::sync::OnceCell;
+
+use super::connect::{connect_options, MIGRATOR};
+
+// Same server as `Docker/db`, so the tests see what compose runs.
+const IMAGE: &str = "mariadb:10.4";
+const LABEL: &str = "axum-ddd-explicit-architecture.test-db";
+const READY_TIMEOUT: Duration = Duration::from_secs(120);
+
+// One container per test binary, started through the docker CLI on first use
+// and published on a free local port. It is removed when the test binary
+// exits; one left behind by a killed run is found with
+// `docker ps -q --filter label=<LABEL>`.
+static URL: OnceLock<String> = OnceLock::new();
+static CONTAINER_ID: OnceLock<String> = OnceLock::new();
+static MIGRATED: OnceCell<()> = OnceCell::const_new();
+
+// Every test runs on a runtime of its own, so each gets its own pool; the
+// schema is migrated once, by whichever test gets here first.
+pub async fn connect() -> Result<MySqlPool, sqlx::Error> {
+    let url = URL.get_or_init(start);
+    let pool = wait_until_ready(url).await?;
+    MIGRATED
+        .get_or_try_init(|| async { MIGRATOR.run(&pool).await.map_err(sqlx::Error::from) })
+        .await?;
+    Ok(pool)
+}
+
+fn start() -> String {
+    let id = docker(&[
+        "run",
+        "-d",
+        "--rm",
+        "--label",
+        LABEL,
+        "-e",
+        "MYSQL_ROOT_PASSWORD=password",
+        "-e",
+        "MYSQL_DATABASE=circles",
+        "-p",
+        "127.0.0.1::3306",
+        IMAGE,
+        "--character-set-server=utf8mb4",
+        "--collation-server=utf8mb4_unicode_ci",
+    ]);
+    let id = CONTAINER_ID.get_or_init(|| id.trim().to_string());
+    // libtest leaves statics alone on exit, so the container is removed from
+    // an `atexit` handler, which runs whether the tests passed or failed.
+    unsafe {
+        libc::atexit(remove_container);
+    }
+    // One line per binding, e.g. `127.0.0.1:49153`.
+    let ports = docker(&["port", id, "3306/tcp"]);
+    let address = ports
+        .lines()
+        .next()
+        .expect("container should publish 3306")
+        .trim();
+    format!("mysql://root:password@{}/circles", address)
+}
+
+extern "C" fn remove_container() {
+    if let Some(id) = CONTAINER_ID.get() {
+        let _ = Command::new("docker").args(["rm", "-f", id]).output();
+    }
+}
+
+fn docker(args: &[&str]) -> String {
+    let output = Command::new("docker")
+        .args(args)
+        .output()
+        .expect("docker should be installed");
+    assert!(
+        output.status.success(),
+        "docker {} failed: {}",
+        args[0],
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("docker output should be UTF-8")
+}
+
+// The server only accepts connections some seconds after the container starts.
+async fn wait_until_ready(url: &str) -> Result<MySqlPool, sqlx::Error> {
+    let started = Instant::now();
+    loop {
+        match MySqlPoolOptions::new()
+            .connect_with(connect_options(url)?)
+            .await
+        {
+            Ok(pool) => return Ok(pool),
+            Err(_) if started.elapsed() < READY_TIMEOUT => {
+                tokio::time::sleep(Duration::from_millis(500)).await
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use dotenv::dotenv;
+
+use super::env_or;
+
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    pub create_rate_limit_per_minute: u32,
+    pub body_limit_bytes: usize,
+    // Responses smaller than this are sent uncompressed.
+    pub compression_min_bytes: u16,
+    // Requests still running after this get a 504.
+    pub request_timeout: Duration,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            create_rate_limit_per_minute: 60,
+            body_limit_bytes: 64 * 1024,
+            compression_min_bytes: 1024,
+            request_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl HttpConfig {
+    pub fn from_env() -> Self {
+        dotenv().ok();
+        let default = Self::default();
+        Self {
+            create_rate_limit_per_minute: env_or(
+                "CREATE_RATE_LIMIT_PER_MINUTE",
+                default.create_rate_limit_per_minute,
+            ),
+            body_limit_bytes: env_or("BODY_LIMIT_BYTES", default.body_limit_bytes),
+            compression_min_bytes: env_or("COMPRESSION_MIN_BYTES", default.compression_min_bytes),
+            request_timeout: Duration::from_secs(env_or(
+                "REQUEST_TIMEOUT_SECS",
+                default.request_timeout.as_secs(),
+            )),
+        }
+    }
+}
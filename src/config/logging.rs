@@ -0,0 +1,69 @@
+use dotenv::dotenv;
+use std::env;
+use tracing_subscriber::{filter::ParseError, EnvFilter};
+
+// `filter` uses the `RUST_LOG` directive syntax, e.g.
+// `info,infrastructure=debug,sqlx=warn`.
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub filter: String,
+    pub json: bool,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            filter: "info".to_string(),
+            json: false,
+        }
+    }
+}
+
+impl LogConfig {
+    pub fn from_env() -> Self {
+        dotenv().ok();
+        let default = Self::default();
+        Self {
+            filter: env::var("RUST_LOG").unwrap_or(default.filter),
+            json: env::var("LOG_FORMAT").is_ok_and(|format| format.eq_ignore_ascii_case("json")),
+        }
+    }
+
+    pub fn env_filter(&self) -> Result<EnvFilter, ParseError> {
+        EnvFilter::builder().parse(&self.filter)
+    }
+
+    pub fn init(&self) {
+        let filter = self
+            .env_filter()
+            .unwrap_or_else(|e| panic!("RUST_LOG is invalid: {}", e));
+        let builder = tracing_subscriber::fmt().with_env_filter(filter);
+        if self.json {
+            builder.json().init();
+        } else {
+            builder.init();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_filter_parses_module_levels() {
+        let config = LogConfig {
+            filter: "warn,infrastructure=debug,axum_ddd_explicit_architecture::handler=trace"
+                .to_string(),
+            json: true,
+        };
+        let filter = config.env_filter().unwrap();
+        assert_eq!(filter.max_level_hint(), Some(tracing::Level::TRACE.into()));
+
+        let config = LogConfig {
+            filter: "infrastructure=loud".to_string(),
+            json: false,
+        };
+        assert!(config.env_filter().is_err());
+    }
+}
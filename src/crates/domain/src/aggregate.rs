@@ -1,3 +1,5 @@
 pub mod circle;
+pub mod circle_policy;
+pub mod circle_stats;
 pub mod member;
 pub mod value_object;
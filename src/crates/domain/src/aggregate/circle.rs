@@ -1,27 +1,55 @@
 use super::{
+    circle_policy::CirclePolicy,
     member::Member,
-    value_object::{circle_id::CircleId, grade::Grade},
+    value_object::{
+        capacity::{Capacity, CapacityError},
+        circle_id::CircleId,
+        grade::Grade,
+        major::Major,
+        member_id::MemberId,
+        member_role::MemberRole,
+        timestamp::Timestamp,
+    },
 };
-use anyhow::Error;
+use crate::error::DomainError;
+
+// The fields a full update may touch; `None` leaves a field as it is.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CircleChanges {
+    pub name: Option<String>,
+    pub capacity: Option<Capacity>,
+    pub owner_id: Option<MemberId>,
+}
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Circle {
     pub id: CircleId,
     pub name: String,
-    pub capacity: i16,
+    pub capacity: Capacity,
     pub owner: Member,
     pub members: Vec<Member>,
+    // Set when the circle is first created; repositories carry it through
+    // reconstruction with `with_created_at`.
+    pub created_at: Option<Timestamp>,
 }
 
 impl Circle {
-    pub fn new(name: String, owner: Member, capacity: i16) -> Result<Self, Error> {
-        if owner.grade != Grade::Third {
-            return Err(Error::msg("Owner must be 3rd grade"));
-        }
+    // Hard limit on the roster, owner included, whatever the capacity; keeps
+    // one request from writing an unbounded number of member rows.
+    pub const MAX_MEMBERS: usize = 500;
 
-        if capacity < 3 {
-            return Err(Error::msg("Circle capacity must be 3 or more"));
-        }
+    pub fn new(name: String, owner: Member, capacity: Capacity) -> Result<Self, DomainError> {
+        Self::new_with_policy(name, owner, capacity, &CirclePolicy::default())
+    }
+
+    pub fn new_with_policy(
+        name: String,
+        mut owner: Member,
+        capacity: Capacity,
+        policy: &CirclePolicy,
+    ) -> Result<Self, DomainError> {
+        Self::check_owner(&owner, policy)?;
+        owner.role = MemberRole::Owner;
 
         Ok(Circle {
             id: CircleId::gen(),
@@ -29,36 +57,213 @@ impl Circle {
             owner,
             capacity,
             members: vec![],
+            created_at: Some(Timestamp::now()),
         })
     }
 
+    // The owner is held apart from `members`, which only lists the other
+    // participants. Storage keeps the owner as one more member row, so
+    // `reconstruct_from_rows` is the safer entry point.
+    #[deprecated(note = "use `reconstruct_from_rows`, which takes the owner inside `members`")]
     pub fn reconstruct(
         id: CircleId,
         name: String,
         owner: Member,
-        capacity: i16,
+        capacity: Capacity,
         members: Vec<Member>,
-    ) -> Self {
-        Circle {
+    ) -> Result<Self, DomainError> {
+        Self::from_parts(id, name, owner, capacity, members)
+    }
+
+    // Takes the roster the way it is stored: every member, owner included,
+    // with `owner_id` naming which one owns the circle.
+    pub fn reconstruct_from_rows(
+        id: CircleId,
+        name: String,
+        owner_id: &MemberId,
+        capacity: Capacity,
+        members: Vec<Member>,
+    ) -> Result<Self, DomainError> {
+        let (mut owners, members): (Vec<Member>, Vec<Member>) = members
+            .into_iter()
+            .partition(|member| &member.id == owner_id);
+        if owners.len() > 1 {
+            return Err(DomainError::InconsistentRoster(
+                "Member is listed more than once",
+            ));
+        }
+        let owner = owners.pop().ok_or(DomainError::InconsistentRoster(
+            "Owner must be listed among members",
+        ))?;
+        Self::from_parts(id, name, owner, capacity, members)
+    }
+
+    // A circle whose members include its owner, or the same member twice, is
+    // rejected, as is one without exactly one `Owner` role.
+    fn from_parts(
+        id: CircleId,
+        name: String,
+        owner: Member,
+        capacity: Capacity,
+        members: Vec<Member>,
+    ) -> Result<Self, DomainError> {
+        if members.iter().any(|member| member.id == owner.id) {
+            return Err(DomainError::InconsistentRoster(
+                "Owner must not be listed among members",
+            ));
+        }
+        for (index, member) in members.iter().enumerate() {
+            if members[..index].iter().any(|other| other.id == member.id) {
+                return Err(DomainError::InconsistentRoster(
+                    "Member is listed more than once",
+                ));
+            }
+        }
+        if owner.role != MemberRole::Owner
+            || members
+                .iter()
+                .any(|member| member.role == MemberRole::Owner)
+        {
+            return Err(DomainError::InconsistentRoster(
+                "Circle must have exactly one owner",
+            ));
+        }
+
+        Ok(Circle {
             id,
             name,
             owner,
             capacity,
             members,
+            created_at: None,
+        })
+    }
+
+    pub fn with_created_at(mut self, created_at: Timestamp) -> Self {
+        self.created_at = Some(created_at);
+        self
+    }
+
+    // A new circle with this one's capacity and a copy of its owner. Members
+    // are carried over only on request; every copied member gets a fresh id.
+    pub fn duplicate(&self, name: String, copy_members: bool) -> Result<Self, DomainError> {
+        let fresh = |member: &Member| {
+            Member::reconstruct(
+                MemberId::gen(),
+                member.name.clone(),
+                member.age,
+                member.grade,
+                member.major.clone(),
+                member.role,
+            )
+        };
+        let members = if copy_members {
+            self.members.iter().map(fresh).collect()
+        } else {
+            vec![]
+        };
+        Ok(Self::from_parts(
+            CircleId::gen(),
+            name,
+            fresh(&self.owner),
+            self.capacity,
+            members,
+        )?
+        .with_created_at(Timestamp::now()))
+    }
+
+    // Takes in everyone on `source`, its owner as a regular member and the
+    // rest with their roles. Anyone already here by id, or by name ignoring
+    // case and surrounding spaces, is skipped and handed back. Either all the
+    // others are admitted as by `add_member` or the circle is left untouched.
+    pub fn merge_from(&mut self, source: &Circle) -> Result<Vec<Member>, DomainError> {
+        let owner = Member {
+            role: MemberRole::Member,
+            ..source.owner.clone()
+        };
+        let mut merged = self.clone();
+        let mut skipped = Vec::new();
+        for member in std::iter::once(owner).chain(source.members.iter().cloned()) {
+            let folded = member.name.trim().to_lowercase();
+            let present = std::iter::once(&merged.owner)
+                .chain(&merged.members)
+                .any(|other| other.id == member.id || other.name.trim().to_lowercase() == folded);
+            if present {
+                skipped.push(member);
+            } else {
+                merged.add_member(member)?;
+            }
         }
+        *self = merged;
+        Ok(skipped)
     }
 
-    pub fn update(&mut self, name: Option<String>, capacity: Option<i16>) {
-        if let Some(name) = name {
-            self.name = name;
+    // Applies every change or none: the first one to break an invariant is
+    // returned and the circle is left untouched.
+    pub fn apply_update(
+        &mut self,
+        changes: CircleChanges,
+        policy: &CirclePolicy,
+    ) -> Result<(), DomainError> {
+        let mut updated = self.clone();
+        if let Some(name) = changes.name {
+            updated.rename(name);
         }
-        if let Some(capacity) = capacity {
-            self.capacity = capacity;
-        };
+        if let Some(capacity) = changes.capacity {
+            updated.change_capacity(capacity)?;
+        }
+        if let Some(owner_id) = changes.owner_id {
+            updated.transfer_ownership(&owner_id, policy)?;
+        }
+        *self = updated;
+        Ok(())
+    }
+
+    pub fn rename(&mut self, name: String) {
+        self.name = name;
+    }
+
+    // The owner counts towards the roster, so capacity can't drop below
+    // `members.len() + 1`.
+    pub fn change_capacity(&mut self, capacity: Capacity) -> Result<(), DomainError> {
+        let members = self.members.len() + 1;
+        if (i16::from(capacity) as usize) < members {
+            return Err(CapacityError::BelowMembership {
+                capacity: capacity.into(),
+                members,
+            }
+            .into());
+        }
+        self.capacity = capacity;
+        Ok(())
+    }
+
+    fn check_owner(owner: &Member, policy: &CirclePolicy) -> Result<(), DomainError> {
+        if owner.grade < policy.owner_min_grade {
+            return Err(DomainError::OwnerGradeTooLow {
+                min_grade: policy.owner_min_grade.into(),
+            });
+        }
+        if owner.age < policy.owner_min_age {
+            return Err(DomainError::OwnerTooYoung {
+                min_age: policy.owner_min_age,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.members.len() + 1 >= i16::from(self.capacity) as usize
+    }
+
+    // Seats left once the owner and members are counted; never negative.
+    pub fn available_slots(&self) -> i16 {
+        let taken = i16::try_from(self.members.len() + 1).unwrap_or(i16::MAX);
+        i16::from(self.capacity).saturating_sub(taken).max(0)
     }
 
-    fn is_full(&self) -> bool {
-        self.members.len() + 1 >= self.capacity as usize
+    pub fn can_accept(&self, n: i16) -> bool {
+        n <= self.available_slots()
     }
 
     fn _is_runnable(&self) -> bool {
@@ -69,22 +274,166 @@ impl Circle {
         member.is_adult()
     }
 
-    pub fn add_member(&mut self, member: Member) -> Result<(), Error> {
+    pub fn add_member(&mut self, member: Member) -> Result<(), DomainError> {
+        self.add_member_with_policy(member, &CirclePolicy::default())
+    }
+
+    pub fn add_member_with_policy(
+        &mut self,
+        member: Member,
+        policy: &CirclePolicy,
+    ) -> Result<(), DomainError> {
+        if self.members.len() + 1 >= Self::MAX_MEMBERS {
+            return Err(DomainError::TooManyMembers {
+                max: Self::MAX_MEMBERS,
+            });
+        }
+
         if self.is_full() {
-            return Err(Error::msg("Circle member is full"));
+            return Err(DomainError::CapacityExceeded);
         }
 
         if member.grade == Grade::Fourth {
-            return Err(Error::msg("4th grade can't join circle"));
+            return Err(DomainError::FourthGradeCannotJoin);
+        }
+
+        if self.has_member(&member.id) {
+            return Err(DomainError::AlreadyMember);
         }
 
+        if member.role == MemberRole::Owner {
+            return Err(DomainError::OwnerAlreadyAssigned);
+        }
+
+        self.check_name_free(&member.id, &member.name, policy)?;
+
         self.members.push(member);
         Ok(())
     }
 
-    pub fn remove_member(&mut self, member: &Member) -> Result<(), Error> {
+    // Changes a member in place, the owner included. The changed member is
+    // held to the rules it was admitted under: the owner to the policy's
+    // minimums, anyone else to staying out of the fourth grade. On error the
+    // circle is left untouched.
+    pub fn update_member(
+        &mut self,
+        member_id: &MemberId,
+        name: Option<String>,
+        age: Option<i16>,
+        grade: Option<Grade>,
+        major: Option<Major>,
+        policy: &CirclePolicy,
+    ) -> Result<(), DomainError> {
+        let mut updated = self
+            .member_mut(member_id)
+            .ok_or(DomainError::MemberNotFound)?
+            .clone();
+        if let Some(name) = &name {
+            self.check_name_free(member_id, name, policy)?;
+        }
+        updated.update(name, age, grade, major);
+        if self.owner.id == *member_id {
+            Self::check_owner(&updated, policy)?;
+        } else if updated.grade == Grade::Fourth {
+            return Err(DomainError::FourthGradeCannotJoin);
+        }
+        if let Some(member) = self.member_mut(member_id) {
+            *member = updated;
+        }
+        Ok(())
+    }
+
+    // Fails when someone other than `member_id` already goes by `name` and the
+    // policy asks for unique names.
+    fn check_name_free(
+        &self,
+        member_id: &MemberId,
+        name: &str,
+        policy: &CirclePolicy,
+    ) -> Result<(), DomainError> {
+        if !policy.unique_member_names {
+            return Ok(());
+        }
+        let folded = name.trim().to_lowercase();
+        let taken = std::iter::once(&self.owner)
+            .chain(&self.members)
+            .any(|other| other.id != *member_id && other.name.trim().to_lowercase() == folded);
+        if taken {
+            return Err(DomainError::DuplicateMemberName(name.trim().to_string()));
+        }
+        Ok(())
+    }
+
+    // Swaps the roster for `members`, each admitted as by `add_member`; on
+    // error the circle is left untouched.
+    pub fn replace_members(&mut self, members: Vec<Member>) -> Result<(), DomainError> {
+        let mut replaced = Circle {
+            members: vec![],
+            ..self.clone()
+        };
+        for member in members {
+            replaced.add_member(member)?;
+        }
+        *self = replaced;
+        Ok(())
+    }
+
+    fn has_member(&self, member_id: &MemberId) -> bool {
+        self.owner.id == *member_id || self.members.iter().any(|m| m.id == *member_id)
+    }
+
+    pub fn member_mut(&mut self, member_id: &MemberId) -> Option<&mut Member> {
+        if self.owner.id == *member_id {
+            return Some(&mut self.owner);
+        }
+        self.members.iter_mut().find(|m| m.id == *member_id)
+    }
+
+    // The previous owner stays in the circle as a regular member.
+    pub fn transfer_ownership(
+        &mut self,
+        member_id: &MemberId,
+        policy: &CirclePolicy,
+    ) -> Result<(), DomainError> {
+        if self.owner.id == *member_id {
+            return Ok(());
+        }
+        let index = self
+            .members
+            .iter()
+            .position(|m| m.id == *member_id)
+            .ok_or(DomainError::OwnerNotMember)?;
+        Self::check_owner(&self.members[index], policy)?;
+
+        let mut new_owner = self.members.remove(index);
+        new_owner.role = MemberRole::Owner;
+        let mut previous_owner = std::mem::replace(&mut self.owner, new_owner);
+        previous_owner.role = MemberRole::Member;
+        self.members.push(previous_owner);
+        Ok(())
+    }
+
+    // Ownership only moves through `transfer_ownership`, so neither granting
+    // nor revoking `Owner` is allowed here.
+    pub fn assign_role(
+        &mut self,
+        member_id: &MemberId,
+        role: MemberRole,
+    ) -> Result<(), DomainError> {
+        if role == MemberRole::Owner || self.owner.id == *member_id {
+            return Err(DomainError::OwnerRoleImmutable);
+        }
+        self.members
+            .iter_mut()
+            .find(|m| m.id == *member_id)
+            .ok_or(DomainError::MemberNotFound)?
+            .role = role;
+        Ok(())
+    }
+
+    pub fn remove_member(&mut self, member: &Member) -> Result<(), DomainError> {
         if self.owner.id == member.id {
-            return Err(Error::msg("Owner can't be removed"));
+            return Err(DomainError::OwnerCannotBeRemoved);
         }
         self.members.retain(|m| m.id != member.id);
         Ok(())
@@ -93,4 +442,671 @@ impl Circle {
     pub fn graduate(&mut self) {
         self.members.retain(|m| m.grade != Grade::Fourth);
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capacity(value: i16) -> Capacity {
+        Capacity::new(value).unwrap()
+    }
+
+    #[test]
+    fn test_owner_min_grade() {
+        let owner = |grade| Member::new("owner".to_string(), 21, grade, Major::Music);
+
+        assert_eq!(
+            Circle::new("Music club".to_string(), owner(Grade::Second), capacity(10)),
+            Err(DomainError::OwnerGradeTooLow { min_grade: 3 })
+        );
+        assert!(Circle::new("Music club".to_string(), owner(Grade::Third), capacity(10)).is_ok());
+        assert!(Circle::new("Music club".to_string(), owner(Grade::Fourth), capacity(10)).is_ok());
+
+        let policy = CirclePolicy {
+            owner_min_grade: Grade::Second,
+            ..CirclePolicy::default()
+        };
+        let new = |grade| {
+            Circle::new_with_policy(
+                "Music club".to_string(),
+                owner(grade),
+                capacity(10),
+                &policy,
+            )
+        };
+        assert_eq!(
+            new(Grade::First),
+            Err(DomainError::OwnerGradeTooLow { min_grade: 2 })
+        );
+        assert!(new(Grade::Second).is_ok());
+    }
+
+    #[test]
+    fn test_owner_min_age() -> anyhow::Result<()> {
+        let owner = |age| Member::new("owner".to_string(), age, Grade::Third, Major::Music);
+
+        assert_eq!(
+            Circle::new("Music club".to_string(), owner(17), capacity(10)),
+            Err(DomainError::OwnerTooYoung { min_age: 18 })
+        );
+        let mut circle = Circle::new("Music club".to_string(), owner(18), capacity(10))?;
+        let junior = Member::new("junior".to_string(), 16, Grade::Third, Major::Art);
+        circle.add_member(junior.clone())?;
+        assert_eq!(
+            circle.transfer_ownership(&junior.id, &CirclePolicy::default()),
+            Err(DomainError::OwnerTooYoung { min_age: 18 })
+        );
+
+        let policy = CirclePolicy {
+            owner_min_age: 21,
+            ..CirclePolicy::default()
+        };
+        let new = |age| {
+            Circle::new_with_policy("Music club".to_string(), owner(age), capacity(10), &policy)
+        };
+        assert_eq!(new(20), Err(DomainError::OwnerTooYoung { min_age: 21 }));
+        assert!(new(21).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_members_applies_below_capacity() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let member = || Member::new("member".to_string(), 19, Grade::First, Major::Art);
+        let mut circle = Circle::new("Music club".to_string(), owner, capacity(Capacity::MAX))?;
+        for _ in 1..Circle::MAX_MEMBERS {
+            circle.add_member(member())?;
+        }
+
+        assert_eq!(circle.members.len() + 1, Circle::MAX_MEMBERS);
+        assert!(!circle.is_full());
+        assert_eq!(
+            circle.add_member(member()),
+            Err(DomainError::TooManyMembers {
+                max: Circle::MAX_MEMBERS
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_available_slots() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let mut circle = Circle::new("Music club".to_string(), owner, capacity(4))?;
+        circle.add_member(Member::new(
+            "member1".to_string(),
+            19,
+            Grade::First,
+            Major::Art,
+        ))?;
+        assert_eq!(circle.available_slots(), 2);
+        assert!(circle.can_accept(2));
+        assert!(!circle.can_accept(3));
+
+        for name in ["member2", "member3"] {
+            circle.add_member(Member::new(name.to_string(), 19, Grade::First, Major::Art))?;
+        }
+        assert_eq!(circle.available_slots(), 0);
+        assert!(!circle.can_accept(1));
+        assert!(circle.can_accept(0));
+        assert_eq!(
+            circle.add_member(Member::new(
+                "member4".to_string(),
+                19,
+                Grade::First,
+                Major::Art
+            )),
+            Err(DomainError::CapacityExceeded)
+        );
+
+        // A capacity below the roster, as rows stored before the check may
+        // hold, still reports no free seats.
+        circle.capacity = capacity(3);
+        assert_eq!(circle.available_slots(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_change_capacity() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let mut circle = Circle::new("Music club".to_string(), owner, capacity(10))?;
+        for name in ["member1", "member2", "member3"] {
+            circle.add_member(Member::new(name.to_string(), 19, Grade::First, Major::Art))?;
+        }
+
+        assert_eq!(
+            circle.change_capacity(capacity(3)),
+            Err(DomainError::Capacity(CapacityError::BelowMembership {
+                capacity: 3,
+                members: 4
+            }))
+        );
+        assert_eq!(circle.capacity, capacity(10));
+
+        circle.change_capacity(capacity(4))?;
+        assert_eq!(circle.capacity, capacity(4));
+        assert!(circle.is_full());
+        circle.change_capacity(capacity(20))?;
+        assert_eq!(circle.capacity, capacity(20));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let mut circle = Circle::new("Music club".to_string(), owner, capacity(10))?;
+        circle.rename("Jazz club".to_string());
+        assert_eq!(circle.name, "Jazz club");
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_member_rejects_duplicates() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let member = Member::new("member".to_string(), 19, Grade::First, Major::Art);
+        let mut circle = Circle::new("Music club".to_string(), owner.clone(), capacity(10))?;
+        circle.add_member(member.clone())?;
+
+        assert_eq!(
+            circle.add_member(member.clone()),
+            Err(DomainError::AlreadyMember)
+        );
+        assert_eq!(circle.add_member(owner), Err(DomainError::AlreadyMember));
+        assert_eq!(
+            circle.add_member(Member::new(
+                "senior".to_string(),
+                22,
+                Grade::Fourth,
+                Major::Art
+            )),
+            Err(DomainError::FourthGradeCannotJoin)
+        );
+        assert_eq!(circle.members, vec![member]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unique_member_names() -> anyhow::Result<()> {
+        let unique = CirclePolicy {
+            unique_member_names: true,
+            ..CirclePolicy::default()
+        };
+        let owner = Member::new("Paul".to_string(), 21, Grade::Third, Major::Music);
+        let member = Member::new("John".to_string(), 19, Grade::First, Major::Art);
+        let namesake = || Member::new(" paul ".to_string(), 19, Grade::First, Major::Art);
+        let mut circle = Circle::new("Music club".to_string(), owner.clone(), capacity(10))?;
+        circle.add_member_with_policy(member.clone(), &unique)?;
+
+        assert_eq!(
+            circle.add_member_with_policy(namesake(), &unique),
+            Err(DomainError::DuplicateMemberName("paul".to_string()))
+        );
+        assert_eq!(
+            circle.update_member(
+                &member.id,
+                Some("PAUL".to_string()),
+                None,
+                None,
+                None,
+                &unique
+            ),
+            Err(DomainError::DuplicateMemberName("PAUL".to_string()))
+        );
+        assert_eq!(circle.members, vec![member.clone()]);
+
+        // Keeping one's own name is not a clash.
+        circle.update_member(
+            &owner.id,
+            Some("paul".to_string()),
+            Some(22),
+            None,
+            None,
+            &unique,
+        )?;
+        assert_eq!(circle.owner.age, 22);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_member_checks_owner_policy() -> anyhow::Result<()> {
+        let policy = CirclePolicy::default();
+        let mut circle = Circle::new(
+            "Music club".to_string(),
+            Member::new("Paul".to_string(), 21, Grade::Third, Major::Music),
+            capacity(10),
+        )?;
+        let owner = circle.owner.clone();
+
+        assert_eq!(
+            circle.update_member(&owner.id, None, None, Some(Grade::Second), None, &policy),
+            Err(DomainError::OwnerGradeTooLow { min_grade: 3 })
+        );
+        assert_eq!(
+            circle.update_member(
+                &owner.id,
+                Some("John".to_string()),
+                Some(17),
+                None,
+                None,
+                &policy
+            ),
+            Err(DomainError::OwnerTooYoung { min_age: 18 })
+        );
+        assert_eq!(circle.owner, owner);
+
+        // The owner may be in the fourth grade.
+        circle.update_member(&owner.id, None, None, Some(Grade::Fourth), None, &policy)?;
+        assert_eq!(circle.owner.grade, Grade::Fourth);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_member_rejects_fourth_grade() -> anyhow::Result<()> {
+        let policy = CirclePolicy::default();
+        let owner = Member::new("Paul".to_string(), 21, Grade::Third, Major::Music);
+        let member = Member::new("John".to_string(), 19, Grade::First, Major::Art);
+        let mut circle = Circle::new("Music club".to_string(), owner, capacity(10))?;
+        circle.add_member(member.clone())?;
+
+        assert_eq!(
+            circle.update_member(
+                &member.id,
+                Some("George".to_string()),
+                None,
+                Some(Grade::Fourth),
+                None,
+                &policy
+            ),
+            Err(DomainError::FourthGradeCannotJoin)
+        );
+        assert_eq!(circle.members, vec![member.clone()]);
+
+        circle.update_member(&member.id, None, None, Some(Grade::Third), None, &policy)?;
+        assert_eq!(circle.members[0].grade, Grade::Third);
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_member_names_allowed_by_default() -> anyhow::Result<()> {
+        let policy = CirclePolicy::default();
+        let owner = Member::new("Paul".to_string(), 21, Grade::Third, Major::Music);
+        let member = Member::new("John".to_string(), 19, Grade::First, Major::Art);
+        let mut circle = Circle::new("Music club".to_string(), owner, capacity(10))?;
+        circle.add_member(member.clone())?;
+
+        circle.add_member(Member::new(
+            "Paul".to_string(),
+            19,
+            Grade::First,
+            Major::Art,
+        ))?;
+        circle.update_member(
+            &member.id,
+            Some("Paul".to_string()),
+            None,
+            None,
+            None,
+            &policy,
+        )?;
+        assert_eq!(circle.members[0].name, "Paul");
+        assert_eq!(
+            circle.update_member(&MemberId::gen(), None, None, None, None, &policy),
+            Err(DomainError::MemberNotFound)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_members() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let kept = Member::new("kept".to_string(), 19, Grade::First, Major::Art);
+        let dropped = Member::new("dropped".to_string(), 19, Grade::First, Major::Art);
+        let mut circle = Circle::new("Music club".to_string(), owner.clone(), capacity(3))?;
+        circle.add_member(kept.clone())?;
+        circle.add_member(dropped)?;
+
+        let added = Member::new("added".to_string(), 20, Grade::Second, Major::Law);
+        circle.replace_members(vec![kept.clone(), added.clone()])?;
+        assert_eq!(circle.members, vec![kept.clone(), added.clone()]);
+
+        let too_many = vec![
+            kept.clone(),
+            added.clone(),
+            Member::new("extra".to_string(), 20, Grade::Second, Major::Law),
+        ];
+        assert_eq!(
+            circle.replace_members(too_many),
+            Err(DomainError::CapacityExceeded)
+        );
+        assert_eq!(
+            circle.replace_members(vec![owner]),
+            Err(DomainError::AlreadyMember)
+        );
+        assert_eq!(circle.members, vec![kept, added]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let member = Member::new("member".to_string(), 19, Grade::First, Major::Art);
+        let mut circle = Circle::new("Music club".to_string(), owner.clone(), capacity(10))?;
+        circle.add_member(member.clone())?;
+
+        let copy = circle.duplicate("Music club 2".to_string(), false)?;
+        assert_ne!(copy.id, circle.id);
+        assert_ne!(copy.owner.id, owner.id);
+        assert_eq!(copy.name, "Music club 2");
+        assert_eq!(copy.capacity, circle.capacity);
+        assert_eq!(copy.owner.name, owner.name);
+        assert!(copy.members.is_empty());
+
+        let copy = circle.duplicate("Music club 3".to_string(), true)?;
+        assert_eq!(copy.members.len(), 1);
+        assert_ne!(copy.members[0].id, member.id);
+        assert_eq!(copy.members[0].name, member.name);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_from() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let member = Member::new("Paul".to_string(), 19, Grade::First, Major::Art);
+        let mut target = Circle::new("Music club".to_string(), owner, capacity(5))?;
+        target.add_member(member)?;
+
+        let source_owner = Member::new("other owner".to_string(), 21, Grade::Third, Major::Art);
+        let newcomer = Member::new("George".to_string(), 19, Grade::First, Major::Art);
+        let namesake = Member::new(" paul ".to_string(), 20, Grade::Second, Major::Law);
+        let mut source = Circle::new("Art club".to_string(), source_owner.clone(), capacity(5))?;
+        source.add_member(newcomer.clone())?;
+        source.add_member(namesake.clone())?;
+
+        let skipped = target.merge_from(&source)?;
+        assert_eq!(skipped, vec![namesake]);
+        let names: Vec<&str> = target.members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["Paul", "other owner", "George"]);
+        assert_eq!(target.members[1].id, source_owner.id);
+        assert_eq!(target.members[1].role, MemberRole::Member);
+        assert_eq!(target.owner.role, MemberRole::Owner);
+
+        let mut nearly_full = Circle::new(
+            "Chess club".to_string(),
+            Member::new("chess owner".to_string(), 21, Grade::Third, Major::Art),
+            capacity(3),
+        )?;
+        nearly_full.add_member(Member::new(
+            "chess member".to_string(),
+            19,
+            Grade::First,
+            Major::Art,
+        ))?;
+        let before = nearly_full.clone();
+        assert_eq!(
+            nearly_full.merge_from(&source),
+            Err(DomainError::CapacityExceeded)
+        );
+        assert_eq!(nearly_full, before);
+        Ok(())
+    }
+
+    #[test]
+    fn test_member_mut() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let member = Member::new("member".to_string(), 19, Grade::First, Major::Art);
+        let mut circle = Circle::new("Music club".to_string(), owner.clone(), capacity(10))?;
+        circle.add_member(member.clone())?;
+
+        circle
+            .member_mut(&member.id)
+            .expect("member should be found")
+            .update(None, None, Some(Grade::Second), None);
+        assert_eq!(circle.members[0].grade, Grade::Second);
+        assert_eq!(circle.members[0].name, "member");
+
+        assert!(circle.member_mut(&owner.id).is_some());
+        assert!(circle.member_mut(&MemberId::gen()).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_transfer_ownership() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let senior = Member::new("senior".to_string(), 21, Grade::Third, Major::Art);
+        let junior = Member::new("junior".to_string(), 19, Grade::First, Major::Art);
+        let mut circle = Circle::new("Music club".to_string(), owner.clone(), capacity(10))?;
+        circle.add_member(senior.clone())?;
+        circle.add_member(junior.clone())?;
+        let policy = CirclePolicy::default();
+
+        assert_eq!(
+            circle.transfer_ownership(&junior.id, &policy),
+            Err(DomainError::OwnerGradeTooLow { min_grade: 3 })
+        );
+        assert_eq!(
+            circle.transfer_ownership(&MemberId::gen(), &policy),
+            Err(DomainError::OwnerNotMember)
+        );
+        assert_eq!(circle.owner.id, owner.id);
+        assert_eq!(circle.owner.role, MemberRole::Owner);
+
+        circle.transfer_ownership(&senior.id, &policy)?;
+        assert_eq!(
+            circle.owner,
+            Member {
+                role: MemberRole::Owner,
+                ..senior
+            }
+        );
+        assert_eq!(circle.members, vec![junior, owner]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_update() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let senior = Member::new("senior".to_string(), 21, Grade::Third, Major::Art);
+        let junior = Member::new("junior".to_string(), 19, Grade::First, Major::Art);
+        let mut circle = Circle::new("Music club".to_string(), owner.clone(), capacity(10))?;
+        circle.add_member(senior.clone())?;
+        circle.add_member(junior.clone())?;
+        circle.add_member(Member::new(
+            "other".to_string(),
+            20,
+            Grade::Second,
+            Major::Law,
+        ))?;
+        let policy = CirclePolicy::default();
+
+        circle.apply_update(
+            CircleChanges {
+                name: Some("Jazz club".to_string()),
+                capacity: Some(capacity(4)),
+                owner_id: Some(senior.id.clone()),
+            },
+            &policy,
+        )?;
+        assert_eq!(circle.name, "Jazz club");
+        assert_eq!(circle.capacity, capacity(4));
+        assert_eq!(circle.owner.id, senior.id);
+
+        let before = circle.clone();
+        let rejected = [
+            CircleChanges {
+                name: Some("Rock club".to_string()),
+                capacity: Some(capacity(3)),
+                ..CircleChanges::default()
+            },
+            CircleChanges {
+                capacity: Some(capacity(5)),
+                owner_id: Some(junior.id.clone()),
+                ..CircleChanges::default()
+            },
+            CircleChanges {
+                name: Some("Rock club".to_string()),
+                owner_id: Some(MemberId::gen()),
+                ..CircleChanges::default()
+            },
+        ];
+        for changes in rejected {
+            assert!(circle.apply_update(changes, &policy).is_err());
+            assert_eq!(circle, before);
+        }
+
+        let error = circle
+            .apply_update(
+                CircleChanges {
+                    owner_id: Some(MemberId::gen()),
+                    ..CircleChanges::default()
+                },
+                &policy,
+            )
+            .unwrap_err();
+        assert_eq!(error, DomainError::OwnerNotMember);
+
+        circle.apply_update(CircleChanges::default(), &policy)?;
+        assert_eq!(circle, before);
+        Ok(())
+    }
+
+    #[test]
+    fn test_assign_role() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let member = Member::new("member".to_string(), 19, Grade::First, Major::Art);
+        let mut circle = Circle::new("Music club".to_string(), owner.clone(), capacity(10))?;
+        circle.add_member(member.clone())?;
+
+        circle.assign_role(&member.id, MemberRole::Officer)?;
+        assert_eq!(circle.members[0].role, MemberRole::Officer);
+
+        assert_eq!(
+            circle.assign_role(&member.id, MemberRole::Owner),
+            Err(DomainError::OwnerRoleImmutable)
+        );
+        assert_eq!(
+            circle.assign_role(&owner.id, MemberRole::Officer),
+            Err(DomainError::OwnerRoleImmutable)
+        );
+        assert_eq!(
+            circle.assign_role(&MemberId::gen(), MemberRole::Officer),
+            Err(DomainError::MemberNotFound)
+        );
+        assert_eq!(
+            circle.add_member(Member {
+                role: MemberRole::Owner,
+                ..Member::new("usurper".to_string(), 19, Grade::First, Major::Art)
+            }),
+            Err(DomainError::OwnerAlreadyAssigned)
+        );
+        assert_eq!(circle.owner.role, MemberRole::Owner);
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_member() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let member = Member::new("member".to_string(), 19, Grade::First, Major::Art);
+        let mut circle = Circle::new("Music club".to_string(), owner, capacity(10))?;
+        circle.add_member(member.clone())?;
+
+        let current_owner = circle.owner.clone();
+        assert_eq!(
+            circle.remove_member(&current_owner),
+            Err(DomainError::OwnerCannotBeRemoved)
+        );
+        circle.remove_member(&member)?;
+        assert!(circle.members.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_reconstruct() -> anyhow::Result<()> {
+        let owner = Member {
+            role: MemberRole::Owner,
+            ..Member::new("owner".to_string(), 21, Grade::Third, Major::Music)
+        };
+        let member = Member::new("member".to_string(), 19, Grade::First, Major::Art);
+
+        let circle = Circle::reconstruct(
+            CircleId::gen(),
+            "Music club".to_string(),
+            owner.clone(),
+            capacity(10),
+            vec![member.clone()],
+        )?;
+        assert_eq!(circle.owner, owner);
+        assert_eq!(circle.members, vec![member.clone()]);
+
+        assert!(Circle::reconstruct(
+            CircleId::gen(),
+            "Music club".to_string(),
+            owner.clone(),
+            capacity(10),
+            vec![owner.clone(), member.clone()],
+        )
+        .is_err_and(|error| matches!(error, DomainError::InconsistentRoster(_))));
+        assert!(Circle::reconstruct(
+            CircleId::gen(),
+            "Music club".to_string(),
+            owner,
+            capacity(10),
+            vec![member.clone(), member.clone()],
+        )
+        .is_err_and(|error| matches!(error, DomainError::InconsistentRoster(_))));
+        assert!(Circle::reconstruct(
+            CircleId::gen(),
+            "Music club".to_string(),
+            member.clone(),
+            capacity(10),
+            vec![],
+        )
+        .is_err_and(|error| matches!(error, DomainError::InconsistentRoster(_))));
+        Ok(())
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_reconstruct_from_rows() -> anyhow::Result<()> {
+        let owner = Member {
+            role: MemberRole::Owner,
+            ..Member::new("owner".to_string(), 21, Grade::Third, Major::Music)
+        };
+        let member = Member::new("member".to_string(), 19, Grade::First, Major::Art);
+        let id = CircleId::gen();
+
+        let held_apart = Circle::reconstruct(
+            id.clone(),
+            "Music club".to_string(),
+            owner.clone(),
+            capacity(10),
+            vec![member.clone()],
+        )?;
+        let from_rows = Circle::reconstruct_from_rows(
+            id,
+            "Music club".to_string(),
+            &owner.id,
+            capacity(10),
+            vec![member.clone(), owner.clone()],
+        )?;
+        assert_eq!(from_rows, held_apart);
+
+        let rows = |members: Vec<Member>| {
+            Circle::reconstruct_from_rows(
+                CircleId::gen(),
+                "Music club".to_string(),
+                &owner.id,
+                capacity(10),
+                members,
+            )
+        };
+        assert!(rows(vec![member.clone()])
+            .is_err_and(|error| matches!(error, DomainError::InconsistentRoster(_))));
+        assert!(rows(vec![owner.clone(), owner.clone(), member.clone()])
+            .is_err_and(|error| matches!(error, DomainError::InconsistentRoster(_))));
+        assert!(rows(vec![owner.clone(), member.clone(), member.clone()])
+            .is_err_and(|error| matches!(error, DomainError::InconsistentRoster(_))));
+        Ok(())
+    }
+}
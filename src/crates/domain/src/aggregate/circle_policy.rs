@@ -0,0 +1,23 @@
+use super::value_object::grade::Grade;
+
+// Club rules that vary between institutions; `Circle` enforces whichever
+// policy it is built with.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CirclePolicy {
+    pub owner_min_grade: Grade,
+    // Applies to the owner only; regular members may be younger.
+    pub owner_min_age: i16,
+    // Some clubs tell members apart by name alone; names are then compared
+    // ignoring case and surrounding whitespace.
+    pub unique_member_names: bool,
+}
+
+impl Default for CirclePolicy {
+    fn default() -> Self {
+        Self {
+            owner_min_grade: Grade::Third,
+            owner_min_age: 18,
+            unique_member_names: false,
+        }
+    }
+}
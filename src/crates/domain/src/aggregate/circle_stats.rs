@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+
+use super::{circle::Circle, value_object::grade::Grade};
+
+// Figures over the whole roster, owner included. Every grade is listed, with
+// zero where nobody is in it; majors only appear once someone takes them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CircleStats {
+    pub average_age: f64,
+    pub grade_distribution: BTreeMap<i16, usize>,
+    pub major_distribution: BTreeMap<String, usize>,
+}
+
+impl CircleStats {
+    pub fn of(circle: &Circle) -> Self {
+        let roster: Vec<_> = std::iter::once(&circle.owner)
+            .chain(&circle.members)
+            .collect();
+        let total_age: i64 = roster.iter().map(|member| i64::from(member.age)).sum();
+
+        let mut grade_distribution: BTreeMap<i16, usize> =
+            [Grade::First, Grade::Second, Grade::Third, Grade::Fourth]
+                .into_iter()
+                .map(|grade| (grade.as_i16(), 0))
+                .collect();
+        let mut major_distribution = BTreeMap::new();
+        for member in &roster {
+            *grade_distribution.entry(member.grade.as_i16()).or_default() += 1;
+            *major_distribution
+                .entry(String::from(member.major.clone()))
+                .or_default() += 1;
+        }
+
+        CircleStats {
+            // The owner is always on the roster, so it is never empty.
+            average_age: total_age as f64 / roster.len() as f64,
+            grade_distribution,
+            major_distribution,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregate::{
+        member::Member,
+        value_object::{capacity::Capacity, major::Major},
+    };
+
+    #[test]
+    fn test_stats_cover_the_whole_roster() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 22, Grade::Third, Major::Music);
+        let mut circle = Circle::new("Music club".to_string(), owner, Capacity::new(10)?)?;
+        circle.add_member(Member::new("a".to_string(), 18, Grade::First, Major::Art))?;
+        circle.add_member(Member::new("b".to_string(), 19, Grade::First, Major::Music))?;
+        circle.add_member(Member::new("c".to_string(), 21, Grade::Third, Major::Law))?;
+
+        let stats = CircleStats::of(&circle);
+        assert_eq!(stats.average_age, 20.0);
+        assert_eq!(
+            stats.grade_distribution,
+            BTreeMap::from([(1, 2), (2, 0), (3, 2), (4, 0)])
+        );
+        assert_eq!(
+            stats.major_distribution,
+            BTreeMap::from([
+                ("Art".to_string(), 1),
+                ("Law".to_string(), 1),
+                ("Music".to_string(), 2),
+            ])
+        );
+        Ok(())
+    }
+}
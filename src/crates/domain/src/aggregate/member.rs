@@ -1,4 +1,6 @@
-use super::value_object::{grade::Grade, major::Major, member_id::MemberId};
+use super::value_object::{
+    grade::Grade, major::Major, member_id::MemberId, member_role::MemberRole,
+};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Member {
@@ -7,6 +9,7 @@ pub struct Member {
     pub age: i16,
     pub grade: Grade,
     pub major: Major,
+    pub role: MemberRole,
 }
 
 impl Member {
@@ -17,16 +20,46 @@ impl Member {
             age,
             grade,
             major,
+            role: MemberRole::Member,
         }
     }
 
-    pub fn reconstruct(id: MemberId, name: String, age: i16, grade: Grade, major: Major) -> Self {
+    pub fn reconstruct(
+        id: MemberId,
+        name: String,
+        age: i16,
+        grade: Grade,
+        major: Major,
+        role: MemberRole,
+    ) -> Self {
         Member {
             id,
             name,
             age,
             grade,
             major,
+            role,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        name: Option<String>,
+        age: Option<i16>,
+        grade: Option<Grade>,
+        major: Option<Major>,
+    ) {
+        if let Some(name) = name {
+            self.name = name;
+        }
+        if let Some(age) = age {
+            self.age = age;
+        }
+        if let Some(grade) = grade {
+            self.grade = grade;
+        }
+        if let Some(major) = major {
+            self.major = major;
         }
     }
 
@@ -1,4 +1,7 @@
+pub mod capacity;
 pub mod circle_id;
 pub mod grade;
 pub mod major;
 pub mod member_id;
+pub mod member_role;
+pub mod timestamp;
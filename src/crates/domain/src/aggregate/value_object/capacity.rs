@@ -0,0 +1,91 @@
+use std::fmt;
+
+#[derive(Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct Capacity(i16);
+
+impl Capacity {
+    pub const MIN: i16 = 3;
+    pub const MAX: i16 = 1000;
+
+    pub fn new(value: i16) -> Result<Self, CapacityError> {
+        if !(Self::MIN..=Self::MAX).contains(&value) {
+            return Err(CapacityError::OutOfRange(value));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl fmt::Display for Capacity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::convert::TryFrom<i16> for Capacity {
+    type Error = CapacityError;
+
+    fn try_from(value: i16) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl std::convert::From<Capacity> for i16 {
+    fn from(value: Capacity) -> Self {
+        value.0
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CapacityError {
+    OutOfRange(i16),
+    // The owner plus members would no longer fit.
+    BelowMembership { capacity: i16, members: usize },
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapacityError::OutOfRange(value) => write!(
+                f,
+                "Circle capacity must be between {} and {}, got {}",
+                Capacity::MIN,
+                Capacity::MAX,
+                value
+            ),
+            CapacityError::BelowMembership { capacity, members } => write!(
+                f,
+                "Circle capacity {} is below its current {} members",
+                capacity, members
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CapacityError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lower_bound() {
+        assert_eq!(i16::from(Capacity::new(Capacity::MIN).unwrap()), 3);
+        assert_eq!(
+            Capacity::new(Capacity::MIN - 1),
+            Err(CapacityError::OutOfRange(2))
+        );
+    }
+
+    #[test]
+    fn test_upper_bound() {
+        assert_eq!(i16::from(Capacity::new(Capacity::MAX).unwrap()), 1000);
+    }
+
+    #[test]
+    fn test_past_max() {
+        assert_eq!(
+            Capacity::try_from(Capacity::MAX + 1),
+            Err(CapacityError::OutOfRange(1001))
+        );
+    }
+}
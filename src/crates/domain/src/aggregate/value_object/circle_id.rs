@@ -2,8 +2,12 @@ use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
+use anyhow::anyhow;
 use rand::distributions::{Alphanumeric, DistString};
 
+// Stored as `VARCHAR(36)` and always bound as its string form. Numeric ids
+// convert both ways through that same string, so `CircleId::from(7)` and
+// `"7".parse()` name the same row.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CircleId(String);
 
@@ -34,12 +38,25 @@ impl FromStr for CircleId {
     }
 }
 
-impl From<i16> for CircleId {
-    fn from(id: i16) -> Self {
+impl From<i64> for CircleId {
+    fn from(id: i64) -> Self {
         Self(id.to_string())
     }
 }
 
+// Generated ids are alphanumeric, so only ones that were numeric to begin
+// with convert back.
+impl TryFrom<&CircleId> for i64 {
+    type Error = anyhow::Error;
+
+    fn try_from(circle_id: &CircleId) -> Result<Self, Self::Error> {
+        circle_id
+            .0
+            .parse()
+            .map_err(|_| anyhow!("circle id {} is not numeric", circle_id.0))
+    }
+}
+
 impl From<CircleId> for String {
     fn from(circle_id: CircleId) -> Self {
         circle_id.0
@@ -60,4 +77,18 @@ mod tests {
         assert_eq!(circle_id.to_string(), str);
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_numeric_round_trip() -> anyhow::Result<()> {
+        let circle_id = CircleId::from(42);
+        assert_eq!(circle_id.to_string(), "42");
+        assert_eq!(CircleId::from_str("42")?, circle_id);
+        assert_eq!(i64::try_from(&circle_id)?, 42);
+        assert_eq!(String::from(circle_id), "42");
+
+        let parsed = CircleId::from_str(&CircleId::from(-7).to_string())?;
+        assert_eq!(i64::try_from(&parsed)?, -7);
+        assert!(i64::try_from(&CircleId::gen()).is_err());
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Copy, Debug, PartialEq, Eq, Hash, Clone)]
+use crate::error::DomainError;
+
+#[derive(Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 pub enum Grade {
     First,
     Second,
@@ -6,9 +8,9 @@ pub enum Grade {
     Fourth,
 }
 
-impl std::convert::From<Grade> for i16 {
-    fn from(value: Grade) -> Self {
-        match value {
+impl Grade {
+    pub fn as_i16(self) -> i16 {
+        match self {
             Grade::First => 1,
             Grade::Second => 2,
             Grade::Third => 3,
@@ -17,8 +19,14 @@ impl std::convert::From<Grade> for i16 {
     }
 }
 
+impl std::convert::From<Grade> for i16 {
+    fn from(value: Grade) -> Self {
+        value.as_i16()
+    }
+}
+
 impl std::convert::TryFrom<i16> for Grade {
-    type Error = anyhow::Error;
+    type Error = DomainError;
 
     fn try_from(value: i16) -> Result<Self, Self::Error> {
         Ok(match value {
@@ -26,11 +34,27 @@ impl std::convert::TryFrom<i16> for Grade {
             2 => Grade::Second,
             3 => Grade::Third,
             4 => Grade::Fourth,
-            _ => anyhow::bail!("error"),
+            _ => return Err(DomainError::InvalidGrade(value)),
         })
     }
 }
 
+// On the wire a grade is its bare number; decoding goes through `try_from`
+// so out-of-range values are rejected rather than clamped.
+impl serde::Serialize for Grade {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i16(self.as_i16())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Grade {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = i16::deserialize(deserializer)?;
+        Grade::try_from(value)
+            .map_err(|_| serde::de::Error::custom(format!("invalid grade: {}", value)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,6 +70,15 @@ mod tests {
             assert_eq!(i16::from(v), n);
             assert_eq!(Grade::try_from(n)?, v);
         }
+        assert_eq!(Grade::try_from(5), Err(DomainError::InvalidGrade(5)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_serde() -> anyhow::Result<()> {
+        assert_eq!(serde_json::to_string(&Grade::Third)?, "3");
+        assert_eq!(serde_json::from_str::<Grade>("3")?, Grade::Third);
+        assert!(serde_json::from_str::<Grade>("9").is_err());
         Ok(())
     }
 }
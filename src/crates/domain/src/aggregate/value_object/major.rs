@@ -1,24 +1,25 @@
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+// Majors outside the well-known set are kept verbatim in `Other` so that any
+// value stored in the database round-trips unchanged.
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Major {
     ComputerScience,
     Economics,
     Law,
     Art,
     Music,
-    Other,
+    Other(String),
 }
 
 impl std::convert::From<Major> for String {
     fn from(value: Major) -> Self {
         match value {
-            Major::ComputerScience => "ComputerScience",
-            Major::Economics => "Economics",
-            Major::Law => "Law",
-            Major::Art => "Art",
-            Major::Music => "Music",
-            Major::Other => "Other",
+            Major::ComputerScience => "ComputerScience".to_string(),
+            Major::Economics => "Economics".to_string(),
+            Major::Law => "Law".to_string(),
+            Major::Art => "Art".to_string(),
+            Major::Music => "Music".to_string(),
+            Major::Other(value) => value,
         }
-        .to_string()
     }
 }
 
@@ -30,7 +31,7 @@ impl std::convert::From<&str> for Major {
             "Law" => Major::Law,
             "Art" => Major::Art,
             "Music" => Major::Music,
-            _ => Major::Other,
+            _ => Major::Other(value.to_string()),
         }
     }
 }
@@ -47,7 +48,8 @@ mod tests {
             (Major::Law, "Law"),
             (Major::Art, "Art"),
             (Major::Music, "Music"),
-            (Major::Other, "Other"),
+            (Major::Other("Other".to_string()), "Other"),
+            (Major::Other("Astrophysics".to_string()), "Astrophysics"),
         ] {
             assert_eq!(Major::from(s), v);
             assert_eq!(String::from(v), s);
@@ -1,8 +1,13 @@
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
+use anyhow::anyhow;
 use rand::distributions::{Alphanumeric, DistString};
 
+// Stored as `VARCHAR(36)` and always bound as its string form. Numeric ids
+// convert both ways through that same string, so `MemberId::from(7)` and
+// `"7".parse()` name the same row.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MemberId(String);
 
@@ -25,7 +30,7 @@ impl fmt::Display for MemberId {
     }
 }
 
-impl std::str::FromStr for MemberId {
+impl FromStr for MemberId {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -33,13 +38,26 @@ impl std::str::FromStr for MemberId {
     }
 }
 
-impl std::convert::From<i16> for MemberId {
-    fn from(id: i16) -> Self {
+impl From<i64> for MemberId {
+    fn from(id: i64) -> Self {
         Self(id.to_string())
     }
 }
 
-impl std::convert::From<MemberId> for String {
+// Generated ids are alphanumeric, so only ones that were numeric to begin
+// with convert back.
+impl TryFrom<&MemberId> for i64 {
+    type Error = anyhow::Error;
+
+    fn try_from(member_id: &MemberId) -> Result<Self, Self::Error> {
+        member_id
+            .0
+            .parse()
+            .map_err(|_| anyhow!("member id {} is not numeric", member_id.0))
+    }
+}
+
+impl From<MemberId> for String {
     fn from(member_id: MemberId) -> Self {
         member_id.0
     }
@@ -47,8 +65,6 @@ impl std::convert::From<MemberId> for String {
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
-
     use super::*;
 
     #[test]
@@ -61,4 +77,18 @@ mod tests {
         assert_eq!(member_id.to_string(), str);
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_numeric_round_trip() -> anyhow::Result<()> {
+        let member_id = MemberId::from(42);
+        assert_eq!(member_id.to_string(), "42");
+        assert_eq!(MemberId::from_str("42")?, member_id);
+        assert_eq!(i64::try_from(&member_id)?, 42);
+        assert_eq!(String::from(member_id), "42");
+
+        let parsed = MemberId::from_str(&MemberId::from(-7).to_string())?;
+        assert_eq!(i64::try_from(&parsed)?, -7);
+        assert!(i64::try_from(&MemberId::gen()).is_err());
+        Ok(())
+    }
+}
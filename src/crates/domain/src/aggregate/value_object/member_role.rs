@@ -0,0 +1,75 @@
+use crate::error::DomainError;
+
+// Exactly one member of a circle, its `owner`, holds `Owner`; `Circle` keeps
+// the role in step with that field.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum MemberRole {
+    Owner,
+    Officer,
+    #[default]
+    Member,
+}
+
+impl MemberRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MemberRole::Owner => "owner",
+            MemberRole::Officer => "officer",
+            MemberRole::Member => "member",
+        }
+    }
+}
+
+impl std::convert::From<MemberRole> for String {
+    fn from(value: MemberRole) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl std::convert::TryFrom<&str> for MemberRole {
+    type Error = DomainError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(match value {
+            "owner" => MemberRole::Owner,
+            "officer" => MemberRole::Officer,
+            "member" => MemberRole::Member,
+            _ => return Err(DomainError::InvalidRole(value.to_string())),
+        })
+    }
+}
+
+impl serde::Serialize for MemberRole {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for MemberRole {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        MemberRole::try_from(value.as_str()).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> anyhow::Result<()> {
+        for (v, s) in [
+            (MemberRole::Owner, "owner"),
+            (MemberRole::Officer, "officer"),
+            (MemberRole::Member, "member"),
+        ] {
+            assert_eq!(MemberRole::try_from(s)?, v);
+            assert_eq!(String::from(v), s);
+        }
+        assert_eq!(
+            MemberRole::try_from("treasurer"),
+            Err(DomainError::InvalidRole("treasurer".to_string()))
+        );
+        Ok(())
+    }
+}
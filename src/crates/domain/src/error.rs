@@ -0,0 +1,74 @@
+use std::fmt;
+
+use crate::aggregate::value_object::capacity::CapacityError;
+
+// Every rule the aggregates enforce fails with one of these, so callers can
+// match on what went wrong instead of on a message. Usecases pass it on
+// inside `anyhow::Error`, where it can be downcast again.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DomainError {
+    Capacity(CapacityError),
+    // The owner and members already fill every seat.
+    CapacityExceeded,
+    // `Circle::MAX_MEMBERS` is reached, however large the capacity.
+    TooManyMembers { max: usize },
+    InvalidGrade(i16),
+    InvalidRole(String),
+    OwnerGradeTooLow { min_grade: i16 },
+    OwnerTooYoung { min_age: i16 },
+    FourthGradeCannotJoin,
+    AlreadyMember,
+    // Only under a policy with `unique_member_names`.
+    DuplicateMemberName(String),
+    OwnerAlreadyAssigned,
+    // Ownership can only pass to someone already on the roster.
+    OwnerNotMember,
+    OwnerRoleImmutable,
+    OwnerCannotBeRemoved,
+    MemberNotFound,
+    // Stored data that no aggregate could have produced.
+    InconsistentRoster(&'static str),
+}
+
+impl fmt::Display for DomainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainError::Capacity(error) => write!(f, "{}", error),
+            DomainError::CapacityExceeded => write!(f, "Circle member is full"),
+            DomainError::TooManyMembers { max } => {
+                write!(f, "A circle can't have more than {} members", max)
+            }
+            DomainError::InvalidGrade(value) => {
+                write!(f, "Grade must be between 1 and 4, got {}", value)
+            }
+            DomainError::InvalidRole(value) => write!(f, "invalid role: {}", value),
+            DomainError::OwnerGradeTooLow { min_grade } => {
+                write!(f, "Owner must be grade {} or higher", min_grade)
+            }
+            DomainError::OwnerTooYoung { min_age } => {
+                write!(f, "Owner must be at least {} years old", min_age)
+            }
+            DomainError::FourthGradeCannotJoin => write!(f, "4th grade can't join circle"),
+            DomainError::AlreadyMember => write!(f, "Member already belongs to the circle"),
+            DomainError::DuplicateMemberName(name) => {
+                write!(f, "A member named {} already belongs to the circle", name)
+            }
+            DomainError::OwnerAlreadyAssigned => write!(f, "Circle already has an owner"),
+            DomainError::OwnerNotMember => write!(f, "New owner must be a member of the circle"),
+            DomainError::OwnerRoleImmutable => {
+                write!(f, "The owner role changes only by transferring ownership")
+            }
+            DomainError::OwnerCannotBeRemoved => write!(f, "Owner can't be removed"),
+            DomainError::MemberNotFound => write!(f, "Member not found in circle"),
+            DomainError::InconsistentRoster(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for DomainError {}
+
+impl std::convert::From<CapacityError> for DomainError {
+    fn from(error: CapacityError) -> Self {
+        DomainError::Capacity(error)
+    }
+}
@@ -1,22 +1,371 @@
+use std::fmt;
+
 use anyhow::Error;
+use futures_core::Stream;
+
+use crate::aggregate::{
+    circle::Circle,
+    member::Member,
+    value_object::{circle_id::CircleId, major::Major, member_id::MemberId, timestamp::Timestamp},
+};
+
+// Returned (wrapped in `anyhow::Error`) when a write collides with an existing
+// circle, e.g. a second circle with the same name.
+#[derive(Debug)]
+pub struct ConflictError {
+    pub message: String,
+}
+
+impl ConflictError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConflictError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ConflictError {}
+
+// Returned (wrapped in `anyhow::Error`) when a lookup matches no circle.
+#[derive(Debug)]
+pub struct NotFoundError {
+    pub message: String,
+}
+
+impl NotFoundError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for NotFoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for NotFoundError {}
+
+// Returned (wrapped in `anyhow::Error`) when a circle's `owner_id` matches
+// none of its member rows. That only happens if stored data is corrupt, so
+// it is kept apart from `NotFoundError` and reported as a server fault.
+#[derive(Debug)]
+pub struct DataIntegrityError {
+    pub circle_id: String,
+    pub owner_id: String,
+}
+
+impl DataIntegrityError {
+    pub fn missing_owner(circle_id: impl Into<String>, owner_id: impl Into<String>) -> Self {
+        Self {
+            circle_id: circle_id.into(),
+            owner_id: owner_id.into(),
+        }
+    }
+}
+
+impl fmt::Display for DataIntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Circle {} has no member row for its owner {}",
+            self.circle_id, self.owner_id
+        )
+    }
+}
+
+impl std::error::Error for DataIntegrityError {}
+
+// Returned (wrapped in `anyhow::Error`) when a repository call is abandoned
+// because the request it serves ran past its deadline.
+#[derive(Debug)]
+pub struct DeadlineExceededError;
+
+impl fmt::Display for DeadlineExceededError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Request deadline passed before the query completed")
+    }
+}
+
+impl std::error::Error for DeadlineExceededError {}
+
+// Returned (wrapped in `anyhow::Error`) when no database connection came free
+// within the pool's acquire timeout; the same call may well succeed shortly.
+#[derive(Debug)]
+pub struct UnavailableError;
+
+impl fmt::Display for UnavailableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Database is unavailable, try again shortly")
+    }
+}
+
+impl std::error::Error for UnavailableError {}
+
+// A failed query that none of the errors above describe, with the database's
+// own error code when it gave one (for MySQL the SQLSTATE, e.g. `42S02`). The
+// driver's error stays reachable underneath through `downcast_ref`.
+#[derive(Debug)]
+pub struct RepositoryError {
+    pub message: String,
+    pub code: Option<String>,
+}
+
+impl fmt::Display for RepositoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for RepositoryError {}
+
+// What `upsert_by_name` did, with the circle as stored afterwards.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Upserted {
+    Created(Circle),
+    Updated(Circle),
+}
+
+// A circle as it was when deleted, with the time of the delete.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArchivedCircle {
+    pub circle: Circle,
+    pub deleted_at: Timestamp,
+}
+
+// A member dropped from a circle's roster, with the time it was dropped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemovedMember {
+    pub member: Member,
+    pub deleted_at: Timestamp,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MembershipChange {
+    Joined,
+    Left,
+}
+
+impl MembershipChange {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MembershipChange::Joined => "joined",
+            MembershipChange::Left => "left",
+        }
+    }
+}
+
+impl std::convert::TryFrom<&str> for MembershipChange {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "joined" => Ok(MembershipChange::Joined),
+            "left" => Ok(MembershipChange::Left),
+            _ => Err(Error::msg(format!("Unknown membership change '{}'", value))),
+        }
+    }
+}
+
+// A member joining or leaving a circle, with the time it happened.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MembershipEvent {
+    pub circle_id: CircleId,
+    pub change: MembershipChange,
+    pub at: Timestamp,
+}
+
+// A write staged on a `CircleTransaction`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CircleWrite {
+    Create(Circle),
+    Update(Circle),
+    Delete(Circle),
+}
+
+// Collects the writes of a `with_transaction` call. Nothing reaches storage
+// until the closure returns `Ok`; then every write lands or none does. A
+// rollback-only transaction still runs every write, so storage-level checks
+// such as unique names apply, but is rolled back instead of committed.
+#[derive(Debug, Default)]
+pub struct CircleTransaction {
+    writes: Vec<CircleWrite>,
+    rollback_only: bool,
+}
+
+impl CircleTransaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn create(&mut self, circle: &Circle) {
+        self.writes.push(CircleWrite::Create(circle.clone()));
+    }
+
+    pub fn update(&mut self, circle: &Circle) {
+        self.writes.push(CircleWrite::Update(circle.clone()));
+    }
+
+    pub fn delete(&mut self, circle: &Circle) {
+        self.writes.push(CircleWrite::Delete(circle.clone()));
+    }
+
+    pub fn set_rollback_only(&mut self) {
+        self.rollback_only = true;
+    }
+
+    pub fn is_rollback_only(&self) -> bool {
+        self.rollback_only
+    }
+
+    pub fn writes(&self) -> &[CircleWrite] {
+        &self.writes
+    }
+
+    pub fn into_writes(self) -> Vec<CircleWrite> {
+        self.writes
+    }
+}
 
-use crate::aggregate::{circle::Circle, value_object::circle_id::CircleId};
 pub trait CircleRepositoryInterface {
     fn find_all(&self) -> impl std::future::Future<Output = Result<Vec<Circle>, Error>> + Send;
+    // Every circle that is not deleted, read as the stream is polled instead
+    // of collected first. The stream owns what it needs, so it can outlive
+    // the call; an error ends it.
+    fn stream_all(&self) -> impl Stream<Item = Result<Circle, Error>> + Send + 'static;
+    // One page ordered by id, plus the number of circles across all pages.
+    fn find_page(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> impl std::future::Future<Output = Result<(Vec<Circle>, i64), Error>> + Send;
+    // Up to `limit` circles whose id sorts after `after`, ordered by id. Unlike
+    // `find_page` the cost does not grow with how deep the page is.
+    fn find_after(
+        &self,
+        after: &CircleId,
+        limit: i64,
+    ) -> impl std::future::Future<Output = Result<Vec<Circle>, Error>> + Send;
+    // The circles among `ids` that exist and are not deleted, each once and in
+    // the order first asked for; missing ids are left out rather than failing.
+    fn find_by_ids(
+        &self,
+        ids: &[CircleId],
+    ) -> impl std::future::Future<Output = Result<Vec<Circle>, Error>> + Send;
     fn find_by_id(
         &self,
         circle_id: &CircleId,
     ) -> impl std::future::Future<Output = Result<Circle, Error>> + Send;
+    fn find_by_member_id(
+        &self,
+        member_id: &MemberId,
+    ) -> impl std::future::Future<Output = Result<Circle, Error>> + Send;
+    fn find_owned_by(
+        &self,
+        owner_id: &MemberId,
+    ) -> impl std::future::Future<Output = Result<Vec<Circle>, Error>> + Send;
+    // Circles whose owner plus members have reached capacity.
+    fn find_full_circles(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<Circle>, Error>> + Send;
+    fn find_by_name_like(
+        &self,
+        pattern: &str,
+    ) -> impl std::future::Future<Output = Result<Vec<Circle>, Error>> + Send;
+    // Circles whose owner studies `major`.
+    fn find_by_owner_major(
+        &self,
+        major: Major,
+    ) -> impl std::future::Future<Output = Result<Vec<Circle>, Error>> + Send;
+    // Deleted circles, most recently deleted first.
+    fn find_archived(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<ArchivedCircle>, Error>> + Send;
+    // Members dropped from the circle and not added back since, in the order
+    // they were dropped. Empty for an unknown circle.
+    fn find_removed_members(
+        &self,
+        circle_id: &CircleId,
+    ) -> impl std::future::Future<Output = Result<Vec<RemovedMember>, Error>> + Send;
+    // Every time the member joined or left a circle, oldest first. Empty for
+    // an unknown member.
+    fn find_membership_history(
+        &self,
+        member_id: &MemberId,
+    ) -> impl std::future::Future<Output = Result<Vec<MembershipEvent>, Error>> + Send;
+    fn count_circles(&self) -> impl std::future::Future<Output = Result<i64, Error>> + Send;
+    fn count_members(&self) -> impl std::future::Future<Output = Result<i64, Error>> + Send;
+    // Runs `f` to stage writes, then applies them in a single transaction.
+    fn with_transaction<F, R>(
+        &self,
+        f: F,
+    ) -> impl std::future::Future<Output = Result<R, Error>> + Send
+    where
+        F: FnOnce(&mut CircleTransaction) -> Result<R, Error> + Send,
+        R: Send;
     fn create(
         &self,
         circle: &Circle,
     ) -> impl std::future::Future<Output = Result<(), Error>> + Send;
+    fn create_many(
+        &self,
+        circles: &[Circle],
+    ) -> impl std::future::Future<Output = Result<(), Error>> + Send;
     fn update(
         &self,
         circle: &Circle,
     ) -> impl std::future::Future<Output = Result<Circle, Error>> + Send;
+    // Persists several aggregates atomically: all of them or none.
+    fn update_many(
+        &self,
+        circles: &[Circle],
+    ) -> impl std::future::Future<Output = Result<(), Error>> + Send;
+    // Replaces every member but the owner in one transaction. Members whose
+    // ids are kept keep their rows; the rest are removed or inserted.
+    fn set_members(
+        &self,
+        circle_id: &CircleId,
+        members: Vec<Member>,
+    ) -> impl std::future::Future<Output = Result<(), Error>> + Send;
+    // Adds `members` to the roster without a read-modify-write of the whole
+    // aggregate. The roster is counted under a lock on the circle, so of two
+    // concurrent adds competing for the last slot only one fits; the other is
+    // a `DomainError::CapacityExceeded` and changes nothing.
+    fn add_members(
+        &self,
+        circle_id: &CircleId,
+        members: &[Member],
+    ) -> impl std::future::Future<Output = Result<(), Error>> + Send;
     fn delete(
         &self,
         circle: &Circle,
     ) -> impl std::future::Future<Output = Result<(), Error>> + Send;
-}
\ No newline at end of file
+    fn restore(
+        &self,
+        circle_id: &CircleId,
+    ) -> impl std::future::Future<Output = Result<(), Error>> + Send;
+    // Adds `delta` to the capacity without a read-modify-write of the whole
+    // aggregate and returns the new capacity. A result out of range or below
+    // the roster, owner included, is a `DomainError::Capacity` and changes
+    // nothing.
+    fn adjust_capacity(
+        &self,
+        circle_id: &CircleId,
+        delta: i16,
+    ) -> impl std::future::Future<Output = Result<i16, Error>> + Send;
+    // Creates `circle` unless another circle already has its name; that one
+    // then only takes `circle`'s capacity, checked as by `change_capacity`.
+    // A soft-deleted circle holding the name is a `ConflictError`.
+    fn upsert_by_name(
+        &self,
+        circle: &Circle,
+    ) -> impl std::future::Future<Output = Result<Upserted, Error>> + Send;
+}
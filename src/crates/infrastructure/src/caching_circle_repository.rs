@@ -0,0 +1,412 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::Error;
+use domain::{
+    aggregate::{
+        circle::Circle,
+        member::Member,
+        value_object::{circle_id::CircleId, major::Major, member_id::MemberId},
+    },
+    interface::circle_repository_interface::{
+        ArchivedCircle, CircleRepositoryInterface, CircleTransaction, CircleWrite, MembershipEvent,
+        RemovedMember, Upserted,
+    },
+};
+use futures_util::Stream;
+
+const DEFAULT_CAPACITY: usize = 1024;
+const DEFAULT_TTL: Duration = Duration::from_secs(30);
+
+// Wraps any repository and keeps `find_by_id` results in memory. Every write
+// that goes through the wrapper drops the entries it touches; writes made
+// behind its back are only seen once the entry expires.
+#[derive(Clone, Debug)]
+pub struct CachingCircleRepository<R> {
+    inner: R,
+    cache: Arc<Mutex<LruCache>>,
+}
+
+impl<R> CachingCircleRepository<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            cache: Arc::new(Mutex::new(LruCache::new(DEFAULT_CAPACITY, DEFAULT_TTL))),
+        }
+    }
+
+    pub fn with_capacity(self, capacity: usize) -> Self {
+        self.cache.lock().unwrap().capacity = capacity;
+        self
+    }
+
+    pub fn with_ttl(self, ttl: Duration) -> Self {
+        self.cache.lock().unwrap().ttl = ttl;
+        self
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    fn cached(&self, circle_id: &CircleId) -> Option<Circle> {
+        self.cache.lock().unwrap().get(&circle_id.to_string())
+    }
+
+    fn store(&self, circle: &Circle) {
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(circle.id.to_string(), circle.clone());
+    }
+
+    fn invalidate<'a>(&self, circle_ids: impl IntoIterator<Item = &'a CircleId>) {
+        let mut cache = self.cache.lock().unwrap();
+        for circle_id in circle_ids {
+            cache.remove(&circle_id.to_string());
+        }
+    }
+}
+
+// Least recently used entries are evicted once `capacity` is reached; a
+// linear scan is fine for the sizes this is meant for.
+#[derive(Debug)]
+struct LruCache {
+    entries: HashMap<String, CacheEntry>,
+    capacity: usize,
+    ttl: Duration,
+    clock: u64,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    circle: Circle,
+    stored_at: Instant,
+    last_used: u64,
+}
+
+impl LruCache {
+    fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity,
+            ttl,
+            clock: 0,
+        }
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn get(&mut self, key: &str) -> Option<Circle> {
+        let now = self.tick();
+        let ttl = self.ttl;
+        match self.entries.get_mut(key) {
+            Some(entry) if entry.stored_at.elapsed() < ttl => {
+                entry.last_used = now;
+                Some(entry.circle.clone())
+            }
+            Some(_) => {
+                self.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&mut self, key: String, circle: Circle) {
+        if self.capacity == 0 {
+            return;
+        }
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            if let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            {
+                self.entries.remove(&oldest);
+            }
+        }
+        let last_used = self.tick();
+        self.entries.insert(
+            key,
+            CacheEntry {
+                circle,
+                stored_at: Instant::now(),
+                last_used,
+            },
+        );
+    }
+
+    fn remove(&mut self, key: &str) {
+        self.entries.remove(key);
+    }
+}
+
+fn written_id(write: &CircleWrite) -> &CircleId {
+    match write {
+        CircleWrite::Create(circle) | CircleWrite::Update(circle) | CircleWrite::Delete(circle) => {
+            &circle.id
+        }
+    }
+}
+
+impl<R> CircleRepositoryInterface for CachingCircleRepository<R>
+where
+    R: CircleRepositoryInterface + Send + Sync,
+{
+    async fn find_all(&self) -> Result<Vec<Circle>, Error> {
+        self.inner.find_all().await
+    }
+
+    fn stream_all(&self) -> impl Stream<Item = Result<Circle, Error>> + Send + 'static {
+        self.inner.stream_all()
+    }
+
+    async fn find_page(&self, limit: i64, offset: i64) -> Result<(Vec<Circle>, i64), Error> {
+        self.inner.find_page(limit, offset).await
+    }
+
+    async fn find_after(&self, after: &CircleId, limit: i64) -> Result<Vec<Circle>, Error> {
+        self.inner.find_after(after, limit).await
+    }
+
+    async fn find_by_ids(&self, ids: &[CircleId]) -> Result<Vec<Circle>, Error> {
+        self.inner.find_by_ids(ids).await
+    }
+
+    async fn find_by_id(&self, circle_id: &CircleId) -> Result<Circle, Error> {
+        if let Some(circle) = self.cached(circle_id) {
+            return Ok(circle);
+        }
+        let circle = self.inner.find_by_id(circle_id).await?;
+        self.store(&circle);
+        Ok(circle)
+    }
+
+    async fn find_by_member_id(&self, member_id: &MemberId) -> Result<Circle, Error> {
+        self.inner.find_by_member_id(member_id).await
+    }
+
+    async fn find_owned_by(&self, owner_id: &MemberId) -> Result<Vec<Circle>, Error> {
+        self.inner.find_owned_by(owner_id).await
+    }
+
+    async fn find_full_circles(&self) -> Result<Vec<Circle>, Error> {
+        self.inner.find_full_circles().await
+    }
+
+    async fn find_by_name_like(&self, pattern: &str) -> Result<Vec<Circle>, Error> {
+        self.inner.find_by_name_like(pattern).await
+    }
+
+    async fn find_by_owner_major(&self, major: Major) -> Result<Vec<Circle>, Error> {
+        self.inner.find_by_owner_major(major).await
+    }
+
+    async fn find_archived(&self) -> Result<Vec<ArchivedCircle>, Error> {
+        self.inner.find_archived().await
+    }
+
+    async fn find_removed_members(
+        &self,
+        circle_id: &CircleId,
+    ) -> Result<Vec<RemovedMember>, Error> {
+        self.inner.find_removed_members(circle_id).await
+    }
+
+    async fn find_membership_history(
+        &self,
+        member_id: &MemberId,
+    ) -> Result<Vec<MembershipEvent>, Error> {
+        self.inner.find_membership_history(member_id).await
+    }
+
+    async fn count_circles(&self) -> Result<i64, Error> {
+        self.inner.count_circles().await
+    }
+
+    async fn count_members(&self) -> Result<i64, Error> {
+        self.inner.count_members().await
+    }
+
+    // The staged writes are only visible inside the closure, so their ids are
+    // copied out there and invalidated once the transaction has run, whether
+    // or not it committed.
+    async fn with_transaction<F, T>(&self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut CircleTransaction) -> Result<T, Error> + Send,
+        T: Send,
+    {
+        let mut written = Vec::new();
+        let result = self
+            .inner
+            .with_transaction(|transaction| {
+                let result = f(transaction);
+                written.extend(transaction.writes().iter().map(written_id).cloned());
+                result
+            })
+            .await;
+        self.invalidate(&written);
+        result
+    }
+
+    async fn create(&self, circle: &Circle) -> Result<(), Error> {
+        self.inner.create(circle).await
+    }
+
+    async fn create_many(&self, circles: &[Circle]) -> Result<(), Error> {
+        self.inner.create_many(circles).await
+    }
+
+    async fn update(&self, circle: &Circle) -> Result<Circle, Error> {
+        let result = self.inner.update(circle).await;
+        self.invalidate([&circle.id]);
+        result
+    }
+
+    async fn update_many(&self, circles: &[Circle]) -> Result<(), Error> {
+        let result = self.inner.update_many(circles).await;
+        self.invalidate(circles.iter().map(|circle| &circle.id));
+        result
+    }
+
+    async fn set_members(&self, circle_id: &CircleId, members: Vec<Member>) -> Result<(), Error> {
+        let result = self.inner.set_members(circle_id, members).await;
+        self.invalidate([circle_id]);
+        result
+    }
+
+    async fn add_members(&self, circle_id: &CircleId, members: &[Member]) -> Result<(), Error> {
+        let result = self.inner.add_members(circle_id, members).await;
+        self.invalidate([circle_id]);
+        result
+    }
+
+    async fn delete(&self, circle: &Circle) -> Result<(), Error> {
+        let result = self.inner.delete(circle).await;
+        self.invalidate([&circle.id]);
+        result
+    }
+
+    async fn restore(&self, circle_id: &CircleId) -> Result<(), Error> {
+        let result = self.inner.restore(circle_id).await;
+        self.invalidate([circle_id]);
+        result
+    }
+
+    async fn adjust_capacity(&self, circle_id: &CircleId, delta: i16) -> Result<i16, Error> {
+        let result = self.inner.adjust_capacity(circle_id, delta).await;
+        self.invalidate([circle_id]);
+        result
+    }
+
+    async fn upsert_by_name(&self, circle: &Circle) -> Result<Upserted, Error> {
+        let result = self.inner.upsert_by_name(circle).await;
+        if let Ok(Upserted::Updated(updated)) = &result {
+            self.invalidate([&updated.id]);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::aggregate::value_object::{capacity::Capacity, grade::Grade};
+
+    use super::*;
+    use crate::circle_repository::CircleRepository;
+
+    fn circle(name: &str) -> Circle {
+        let owner = Member::new(
+            "owner".to_string(),
+            21,
+            Grade::Third,
+            Major::ComputerScience,
+        );
+        Circle::new(name.to_string(), owner, Capacity::new(5).unwrap()).unwrap()
+    }
+
+    // Renames go straight to the inner repository, so a stale name coming
+    // back proves the read never reached it.
+    #[tokio::test]
+    async fn test_find_by_id_is_served_from_cache_within_ttl() -> anyhow::Result<()> {
+        let inner = CircleRepository::new();
+        let repository = CachingCircleRepository::new(inner.clone());
+        let mut circle = circle("cached");
+        inner.create(&circle).await?;
+
+        assert_eq!(repository.find_by_id(&circle.id).await?.name, "cached");
+        circle.name = "renamed".to_string();
+        inner.update(&circle).await?;
+        assert_eq!(repository.find_by_id(&circle.id).await?.name, "cached");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_entries_expire_after_ttl() -> anyhow::Result<()> {
+        let inner = CircleRepository::new();
+        let repository =
+            CachingCircleRepository::new(inner.clone()).with_ttl(Duration::from_millis(20));
+        let mut circle = circle("expiring");
+        inner.create(&circle).await?;
+
+        repository.find_by_id(&circle.id).await?;
+        circle.name = "renamed".to_string();
+        inner.update(&circle).await?;
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(repository.find_by_id(&circle.id).await?.name, "renamed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_writes_invalidate_entries() -> anyhow::Result<()> {
+        let repository = CachingCircleRepository::new(CircleRepository::new());
+        let mut circle = circle("invalidated");
+        repository.create(&circle).await?;
+        repository.find_by_id(&circle.id).await?;
+
+        circle.name = "updated".to_string();
+        repository.update(&circle).await?;
+        assert_eq!(repository.find_by_id(&circle.id).await?.name, "updated");
+
+        circle.name = "in transaction".to_string();
+        repository
+            .with_transaction(|transaction| {
+                transaction.update(&circle);
+                Ok(())
+            })
+            .await?;
+        assert_eq!(
+            repository.find_by_id(&circle.id).await?.name,
+            "in transaction"
+        );
+
+        repository.delete(&circle).await?;
+        assert!(repository.find_by_id(&circle.id).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_least_recently_used_entry_is_evicted() -> anyhow::Result<()> {
+        let inner = CircleRepository::new();
+        let repository = CachingCircleRepository::new(inner.clone()).with_capacity(1);
+        let mut first = circle("first");
+        let second = circle("second");
+        inner.create(&first).await?;
+        inner.create(&second).await?;
+
+        repository.find_by_id(&first.id).await?;
+        repository.find_by_id(&second.id).await?;
+        first.name = "renamed".to_string();
+        inner.update(&first).await?;
+        assert_eq!(repository.find_by_id(&first.id).await?.name, "renamed");
+        Ok(())
+    }
+}
@@ -1,207 +1,1323 @@
+use std::{
+    collections::HashSet,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use anyhow::Error;
 use domain::{
-    aggregate::{circle::Circle, value_object::circle_id::CircleId},
-    interface::circle_repository_interface::CircleRepositoryInterface,
+    aggregate::{
+        circle::Circle,
+        member::Member,
+        value_object::{
+            capacity::Capacity, circle_id::CircleId, grade::Grade, major::Major,
+            member_id::MemberId, member_role::MemberRole, timestamp::Timestamp,
+        },
+    },
+    error::DomainError,
+    interface::circle_repository_interface::{
+        ArchivedCircle, CircleRepositoryInterface, CircleTransaction, CircleWrite, ConflictError,
+        MembershipChange, MembershipEvent, NotFoundError, RemovedMember, Upserted,
+    },
 };
-use sqlx::Row;
+use futures_util::Stream;
 
-use super::db_data::{circle_data::CircleData, member_data::MemberData};
+use super::db::Db;
 
 #[derive(Clone, Debug)]
-pub struct CircleRepositoryWithMySql {
-    db: sqlx::MySqlPool,
+pub struct CircleRepository {
+    db: Db,
+    // Orders membership history across circles, standing in for the
+    // auto-increment id of `membership_history`.
+    sequence: Arc<AtomicU64>,
 }
 
-impl CircleRepositoryWithMySql {
-    pub fn new(db: sqlx::MySqlPool) -> Self {
-        Self { db }
+impl CircleRepository {
+    pub fn new() -> Self {
+        Self {
+            db: Db::new(),
+            sequence: Arc::new(AtomicU64::new(0)),
+        }
     }
-}
 
-impl CircleRepositoryInterface for CircleRepositoryWithMySql {
-    async fn find_all(&self) -> Result<Vec<Circle>, anyhow::Error> {
-        tracing::info!("find_all_circles");
-        let circle_query = sqlx::query("SELECT * FROM circles");
+    fn get_active<K: AsRef<str>>(&self, key: K) -> Result<Option<CircleData>, Error> {
+        Ok(self
+            .db
+            .get::<CircleData, _>(key)?
+            .filter(|data| data.deleted_at.is_none()))
+    }
 
-        let circle_rows = circle_query.fetch_all(&self.db).await.map_err(|e| {
-            eprintln!("Failed to fetch circles: {:?}", e);
-            anyhow::Error::msg("Failed to fetch circles")
-        })?;
+    // Soft-deleted circles keep their name, as they do under the unique index
+    // on `circles.name`.
+    fn check_name_available(&self, circle: &Circle) -> Result<(), Error> {
+        for key in self.db.keys() {
+            if let Some(data) = self.db.get::<CircleData, _>(key)? {
+                if data.name == circle.name && data.id != String::from(circle.id.clone()) {
+                    return Err(name_taken(&circle.name));
+                }
+            }
+        }
+        Ok(())
+    }
 
-        let mut circles = Vec::new();
-        for circle_row in circle_rows {
-            let member_query = sqlx::query("SELECT * FROM members WHERE circle_id = ?")
-                .bind(circle_row.get::<i16, _>("id"));
-
-            let members_row = member_query.fetch_all(&self.db).await.map_err(|e| {
-                eprintln!("Failed to fetch members by circle id: {:?}", e);
-                anyhow::Error::msg("Failed to fetch members by circle id")
-            })?;
-
-            let members: Vec<MemberData> = members_row
-                .into_iter()
-                .map(|member| MemberData {
-                    id: member.get::<String, _>("id"),
-                    name: member.get::<String, _>("name"),
-                    age: member.get::<i16, _>("age"),
-                    grade: member.get::<i16, _>("grade"),
-                    major: member.get::<String, _>("major"),
-                })
-                .collect();
+    fn history_entry(&self, member_id: &str, change: MembershipChange, at: i64) -> HistoryData {
+        HistoryData {
+            member_id: member_id.to_string(),
+            change: change.as_str().to_string(),
+            at,
+            sequence: self.sequence.fetch_add(1, Ordering::Relaxed),
+        }
+    }
 
-            let owner: MemberData = members
-                .iter()
-                .find(|member| member.id == circle_row.get::<String, _>("owner_id"))
-                .ok_or_else(|| anyhow::Error::msg("Owner not found"))?
-                .clone();
+    // The row for a newly stored `circle`, with everyone on it joining.
+    fn new_data(&self, circle: &Circle) -> CircleData {
+        let mut data = CircleData::from(circle.clone());
+        let now = Timestamp::now().unix_seconds();
+        data.history = std::iter::once(&data.owner)
+            .chain(&data.members)
+            .map(|member| self.history_entry(&member.id, MembershipChange::Joined, now))
+            .collect();
+        data
+    }
 
-            let circle_data = CircleData {
-                id: circle_row.get::<String, _>("id"),
-                name: circle_row.get::<String, _>("name"),
-                owner_id: circle_row.get::<String, _>("owner_id"),
-                owner,
-                capacity: circle_row.get::<i16, _>("capacity"),
-                members,
-            };
+    // The stored row for `circle`, keeping the members dropped earlier and
+    // adding the ones it no longer has, as the members table keeps their rows.
+    // Whoever is new to the roster joins and whoever is gone leaves.
+    fn next_data(&self, circle: &Circle) -> Result<CircleData, Error> {
+        let Some(previous) = self.db.get::<CircleData, _>(circle.id.to_string())? else {
+            return Ok(self.new_data(circle));
+        };
+        let mut data = CircleData::from(circle.clone());
+        let roster: HashSet<String> = std::iter::once(&data.owner)
+            .chain(&data.members)
+            .map(|member| member.id.clone())
+            .collect();
+        let previous_roster: HashSet<String> = std::iter::once(&previous.owner)
+            .chain(&previous.members)
+            .map(|member| member.id.clone())
+            .collect();
+        let now = Timestamp::now().unix_seconds();
+        data.history = previous.history;
+        for member in std::iter::once(&previous.owner).chain(&previous.members) {
+            if !roster.contains(&member.id) {
+                let entry = self.history_entry(&member.id, MembershipChange::Left, now);
+                data.history.push(entry);
+            }
+        }
+        for member in std::iter::once(&data.owner).chain(&data.members) {
+            if !previous_roster.contains(&member.id) {
+                let entry = self.history_entry(&member.id, MembershipChange::Joined, now);
+                data.history.push(entry);
+            }
+        }
+        data.removed = previous
+            .removed
+            .into_iter()
+            .filter(|removed| !roster.contains(&removed.member.id))
+            .chain(
+                std::iter::once(previous.owner)
+                    .chain(previous.members)
+                    .filter(|member| !roster.contains(&member.id))
+                    .map(|member| RemovedMemberData {
+                        member,
+                        deleted_at: now,
+                    }),
+            )
+            .collect();
+        Ok(data)
+    }
 
-            circles.push(Circle::try_from(circle_data)?);
+    fn apply(&self, write: &CircleWrite) -> Result<(), Error> {
+        match write {
+            CircleWrite::Create(circle) => {
+                if self
+                    .db
+                    .get::<CircleData, _>(circle.id.to_string())?
+                    .is_some()
+                {
+                    return Err(Error::msg("Circle already exists"));
+                }
+                self.check_name_available(circle)?;
+                self.db.set(circle.id.to_string(), &self.new_data(circle))
+            }
+            CircleWrite::Update(circle) => {
+                if self.get_active(circle.id.to_string())?.is_none() {
+                    return Err(Error::msg("Circle not found"));
+                }
+                self.check_name_available(circle)?;
+                self.db.set(circle.id.to_string(), &self.next_data(circle)?)
+            }
+            CircleWrite::Delete(circle) => match self.get_active(circle.id.to_string())? {
+                Some(mut data) => {
+                    data.deleted_at = Some(Timestamp::now().unix_seconds());
+                    self.db.set(circle.id.to_string(), &data)
+                }
+                None => Err(Error::msg("Circle not found")),
+            },
         }
+    }
+}
 
-        Ok(circles)
+fn name_taken(name: &str) -> Error {
+    ConflictError::new(format!("Circle name '{}' is already taken", name)).into()
+}
+
+impl Default for CircleRepository {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    async fn find_by_id(&self, circle_id: &CircleId) -> Result<Circle, anyhow::Error> {
-        tracing::info!("find_circle_by_id : {:?}", circle_id);
-        let circle_query =
-            sqlx::query("SELECT * FROM circles WHERE id = ?").bind(circle_id.to_string());
+impl CircleRepositoryInterface for CircleRepository {
+    async fn find_all(&self) -> Result<Vec<Circle>, Error> {
+        self.db
+            .keys()
+            .into_iter()
+            .filter_map(|key| self.get_active(key).transpose())
+            .map(|data| data.and_then(Circle::try_from))
+            .collect()
+    }
 
-        let circle_row = circle_query.fetch_one(&self.db).await.map_err(|e| {
-            eprintln!("Failed to fetch circle by id: {:?}", e);
-            anyhow::Error::msg("Failed to fetch circle by id")
-        })?;
+    // Everything is already in memory, so the circles are read up front and
+    // handed out one at a time.
+    fn stream_all(&self) -> impl Stream<Item = Result<Circle, Error>> + Send + 'static {
+        let circles: Vec<Result<Circle, Error>> = self
+            .db
+            .keys()
+            .into_iter()
+            .filter_map(|key| self.get_active(key).transpose())
+            .map(|data| data.and_then(Circle::try_from))
+            .collect();
+        futures_util::stream::iter(circles)
+    }
 
-        let member_query =
-            sqlx::query("SELECT * FROM members WHERE circle_id = ?").bind(circle_id.to_string());
+    async fn find_page(&self, limit: i64, offset: i64) -> Result<(Vec<Circle>, i64), Error> {
+        let mut circles = self.find_all().await?;
+        circles.sort_by_key(|circle| circle.id.to_string());
+        let total = circles.len() as i64;
+        let page = circles
+            .into_iter()
+            .skip(offset.max(0) as usize)
+            .take(limit.max(0) as usize)
+            .collect();
+        Ok((page, total))
+    }
 
-        let members_row = member_query.fetch_all(&self.db).await.map_err(|e| {
-            eprintln!("Failed to fetch members by circle id: {:?}", e);
-            anyhow::Error::msg("Failed to fetch members by circle id")
-        })?;
+    async fn find_after(&self, after: &CircleId, limit: i64) -> Result<Vec<Circle>, Error> {
+        let after = after.to_string();
+        let mut circles = self.find_all().await?;
+        circles.retain(|circle| circle.id.to_string() > after);
+        circles.sort_by_key(|circle| circle.id.to_string());
+        circles.truncate(limit.max(0) as usize);
+        Ok(circles)
+    }
+
+    async fn find_by_ids(&self, ids: &[CircleId]) -> Result<Vec<Circle>, Error> {
+        let mut seen = HashSet::new();
+        let mut circles = Vec::new();
+        for circle_id in ids {
+            if !seen.insert(circle_id.to_string()) {
+                continue;
+            }
+            if let Some(data) = self.get_active(circle_id.to_string())? {
+                circles.push(Circle::try_from(data)?);
+            }
+        }
+        Ok(circles)
+    }
+
+    async fn find_by_id(&self, circle_id: &CircleId) -> Result<Circle, Error> {
+        match self.get_active(circle_id.to_string())? {
+            Some(data) => Ok(Circle::try_from(data)?),
+            None => Err(NotFoundError::new("Circle not found").into()),
+        }
+    }
 
-        let members: Vec<MemberData> = members_row
+    async fn find_by_member_id(&self, member_id: &MemberId) -> Result<Circle, Error> {
+        self.find_all()
+            .await?
             .into_iter()
-            .map(|member| MemberData {
-                id: member.get::<String, _>("id"),
-                name: member.get::<String, _>("name"),
-                age: member.get::<i16, _>("age"),
-                grade: member.get::<i16, _>("grade"),
-                major: member.get::<String, _>("major"),
+            .find(|circle| {
+                circle.owner.id == *member_id
+                    || circle.members.iter().any(|member| member.id == *member_id)
             })
+            .ok_or_else(|| NotFoundError::new("Circle not found for member").into())
+    }
+
+    async fn find_owned_by(&self, owner_id: &MemberId) -> Result<Vec<Circle>, Error> {
+        Ok(self
+            .find_all()
+            .await?
+            .into_iter()
+            .filter(|circle| circle.owner.id == *owner_id)
+            .collect())
+    }
+
+    async fn find_full_circles(&self) -> Result<Vec<Circle>, Error> {
+        Ok(self
+            .find_all()
+            .await?
+            .into_iter()
+            .filter(Circle::is_full)
+            .collect())
+    }
+
+    async fn find_by_name_like(&self, pattern: &str) -> Result<Vec<Circle>, Error> {
+        let pattern = pattern.to_lowercase();
+        Ok(self
+            .find_all()
+            .await?
+            .into_iter()
+            .filter(|circle| circle.name.to_lowercase().contains(&pattern))
+            .collect())
+    }
+
+    async fn find_by_owner_major(&self, major: Major) -> Result<Vec<Circle>, Error> {
+        Ok(self
+            .find_all()
+            .await?
+            .into_iter()
+            .filter(|circle| circle.owner.major == major)
+            .collect())
+    }
+
+    async fn find_archived(&self) -> Result<Vec<ArchivedCircle>, Error> {
+        let mut archived = Vec::new();
+        for key in self.db.keys() {
+            if let Some(mut data) = self.db.get::<CircleData, _>(key)? {
+                if let Some(deleted_at) = data.deleted_at.take() {
+                    archived.push(ArchivedCircle {
+                        circle: Circle::try_from(data)?,
+                        deleted_at: Timestamp::from_unix_seconds(deleted_at),
+                    });
+                }
+            }
+        }
+        archived.sort_by_key(|archived| std::cmp::Reverse(archived.deleted_at));
+        Ok(archived)
+    }
+
+    // A member moved to another circle is live again, just not here, so it is
+    // left out as it is from the members table.
+    async fn find_removed_members(
+        &self,
+        circle_id: &CircleId,
+    ) -> Result<Vec<RemovedMember>, Error> {
+        let Some(data) = self.get_active(circle_id.to_string())? else {
+            return Ok(Vec::new());
+        };
+        let live: HashSet<MemberId> = self
+            .find_all()
+            .await?
+            .into_iter()
+            .flat_map(|circle| std::iter::once(circle.owner).chain(circle.members))
+            .map(|member| member.id)
             .collect();
+        let mut removed = Vec::new();
+        for entry in data.removed {
+            let member = Member::try_from(entry.member)?;
+            if !live.contains(&member.id) {
+                removed.push(RemovedMember {
+                    member,
+                    deleted_at: Timestamp::from_unix_seconds(entry.deleted_at),
+                });
+            }
+        }
+        Ok(removed)
+    }
 
-        let owner: MemberData = members
+    // Deleted circles are searched too; leaving through a delete is not
+    // recorded, as the members stay to be restored.
+    async fn find_membership_history(
+        &self,
+        member_id: &MemberId,
+    ) -> Result<Vec<MembershipEvent>, Error> {
+        let member_id = member_id.to_string();
+        let mut entries = Vec::new();
+        for key in self.db.keys() {
+            if let Some(data) = self.db.get::<CircleData, _>(key)? {
+                let circle_id = CircleId::from_str(&data.id)?;
+                for entry in data.history {
+                    if entry.member_id == member_id {
+                        entries.push((circle_id.clone(), entry));
+                    }
+                }
+            }
+        }
+        entries.sort_by_key(|(_, entry)| entry.sequence);
+        entries
+            .into_iter()
+            .map(|(circle_id, entry)| {
+                Ok(MembershipEvent {
+                    circle_id,
+                    change: MembershipChange::try_from(entry.change.as_str())?,
+                    at: Timestamp::from_unix_seconds(entry.at),
+                })
+            })
+            .collect()
+    }
+
+    async fn count_circles(&self) -> Result<i64, Error> {
+        Ok(self.find_all().await?.len() as i64)
+    }
+
+    // The owner is counted too, matching the members table where the owner
+    // has a row of its own.
+    async fn count_members(&self) -> Result<i64, Error> {
+        Ok(self
+            .find_all()
+            .await?
             .iter()
-            .find(|member| member.id == circle_row.get::<String, _>("owner_id"))
-            .ok_or_else(|| anyhow::Error::msg("Owner not found"))?
-            .clone();
-
-        let circle_data = CircleData {
-            id: circle_row.get::<String, _>("id"),
-            name: circle_row.get::<String, _>("name"),
-            owner_id: circle_row.get::<String, _>("owner_id"),
-            owner,
-            capacity: circle_row.get::<i16, _>("capacity"),
-            members,
+            .map(|circle| 1 + circle.members.len() as i64)
+            .sum())
+    }
+
+    // Writes are applied one by one against the live map; a failure, or a
+    // rollback-only transaction, puts the snapshot taken beforehand back.
+    async fn with_transaction<F, R>(&self, f: F) -> Result<R, Error>
+    where
+        F: FnOnce(&mut CircleTransaction) -> Result<R, Error> + Send,
+        R: Send,
+    {
+        let mut transaction = CircleTransaction::new();
+        let result = f(&mut transaction)?;
+        let rollback_only = transaction.is_rollback_only();
+        let snapshot = self.db.snapshot();
+        for write in transaction.into_writes() {
+            if let Err(e) = self.apply(&write) {
+                self.db.restore(snapshot);
+                return Err(e);
+            }
+        }
+        if rollback_only {
+            self.db.restore(snapshot);
+        }
+        Ok(result)
+    }
+
+    async fn create(&self, circle: &Circle) -> Result<(), Error> {
+        self.with_transaction(|transaction| {
+            transaction.create(circle);
+            Ok(())
+        })
+        .await
+    }
+
+    async fn create_many(&self, circles: &[Circle]) -> Result<(), Error> {
+        for (index, circle) in circles.iter().enumerate() {
+            if self
+                .db
+                .get::<CircleData, _>(circle.id.to_string())?
+                .is_some()
+            {
+                return Err(Error::msg("Circle already exists"));
+            }
+            self.check_name_available(circle)?;
+            if circles[..index]
+                .iter()
+                .any(|other| other.name == circle.name)
+            {
+                return Err(name_taken(&circle.name));
+            }
+        }
+        for circle in circles {
+            self.db.set(circle.id.to_string(), &self.new_data(circle))?;
+        }
+        Ok(())
+    }
+
+    async fn update(&self, circle: &Circle) -> Result<Circle, Error> {
+        match self.get_active(circle.id.to_string())? {
+            Some(_) => {
+                self.check_name_available(circle)?;
+                self.db
+                    .set(circle.id.to_string(), &self.next_data(circle)?)
+                    .and_then(|_| self.db.get::<CircleData, _>(&circle.id.to_string()))
+                    .map(|data| match data {
+                        Some(data) => Circle::try_from(data),
+                        None => Err(Error::msg("Failed to convert circle data")),
+                    })?
+            }
+            None => Err(Error::msg("Circle not found")),
+        }
+    }
+
+    async fn update_many(&self, circles: &[Circle]) -> Result<(), Error> {
+        for circle in circles {
+            if self.get_active(circle.id.to_string())?.is_none() {
+                return Err(Error::msg("Circle not found"));
+            }
+            self.check_name_available(circle)?;
+        }
+        for circle in circles {
+            self.db
+                .set(circle.id.to_string(), &self.next_data(circle)?)?;
+        }
+        Ok(())
+    }
+
+    async fn set_members(&self, circle_id: &CircleId, members: Vec<Member>) -> Result<(), Error> {
+        let mut circle = self.find_by_id(circle_id).await?;
+        circle.replace_members(members)?;
+        self.apply(&CircleWrite::Update(circle))
+    }
+
+    async fn add_members(&self, circle_id: &CircleId, members: &[Member]) -> Result<(), Error> {
+        let mut circle = match self.get_active(circle_id.to_string())? {
+            Some(data) => Circle::try_from(data)?,
+            None => return Err(NotFoundError::new("Circle not found").into()),
+        };
+        for member in members {
+            circle.add_member(member.clone())?;
+        }
+        self.db
+            .set(circle_id.to_string(), &self.next_data(&circle)?)
+    }
+
+    async fn delete(&self, circle: &Circle) -> Result<(), Error> {
+        self.apply(&CircleWrite::Delete(circle.clone()))
+    }
+
+    // An owner who has since gone to another circle, as a merge does, keeps
+    // the circle deleted.
+    async fn restore(&self, circle_id: &CircleId) -> Result<(), Error> {
+        match self.db.get::<CircleData, _>(circle_id.to_string())? {
+            Some(mut data)
+                if data.deleted_at.is_some()
+                    && self
+                        .find_by_member_id(&MemberId::from_str(&data.owner.id)?)
+                        .await
+                        .is_err() =>
+            {
+                data.deleted_at = None;
+                self.db.set(circle_id.to_string(), &data)
+            }
+            _ => Err(NotFoundError::new("Circle not found").into()),
+        }
+    }
+
+    async fn adjust_capacity(&self, circle_id: &CircleId, delta: i16) -> Result<i16, Error> {
+        let mut circle = match self.get_active(circle_id.to_string())? {
+            Some(data) => Circle::try_from(data)?,
+            None => return Err(NotFoundError::new("Circle not found").into()),
         };
+        let capacity = Capacity::try_from(i16::from(circle.capacity).saturating_add(delta))
+            .map_err(DomainError::from)?;
+        circle.change_capacity(capacity)?;
+        self.db
+            .set(circle_id.to_string(), &self.next_data(&circle)?)?;
+        Ok(capacity.into())
+    }
 
-        Ok(Circle::try_from(circle_data)?)
+    async fn upsert_by_name(&self, circle: &Circle) -> Result<Upserted, Error> {
+        let mut existing = None;
+        for key in self.db.keys() {
+            if let Some(data) = self.db.get::<CircleData, _>(key)? {
+                if data.name == circle.name {
+                    existing = Some(data);
+                    break;
+                }
+            }
+        }
+        match existing {
+            None => {
+                self.apply(&CircleWrite::Create(circle.clone()))?;
+                Ok(Upserted::Created(circle.clone()))
+            }
+            Some(data) if data.deleted_at.is_some() => Err(name_taken(&circle.name)),
+            Some(data) => {
+                let mut updated = Circle::try_from(data)?;
+                updated.change_capacity(circle.capacity)?;
+                self.db
+                    .set(updated.id.to_string(), &self.next_data(&updated)?)?;
+                Ok(Upserted::Updated(updated))
+            }
+        }
     }
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct CircleData {
+    id: String,
+    name: String,
+    owner: MemberData,
+    capacity: i16,
+    members: Vec<MemberData>,
+    // Unix seconds; set while the circle is soft-deleted.
+    #[serde(default)]
+    deleted_at: Option<i64>,
+    #[serde(default)]
+    created_at: Option<i64>,
+    // Members dropped from the roster, oldest first.
+    #[serde(default)]
+    removed: Vec<RemovedMemberData>,
+    // Members joining and leaving, oldest first.
+    #[serde(default)]
+    history: Vec<HistoryData>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct RemovedMemberData {
+    member: MemberData,
+    // Unix seconds.
+    deleted_at: i64,
+}
 
-    async fn create(&self, circle: &Circle) -> Result<(), anyhow::Error> {
-        tracing::info!("create_circle : {:?}", circle);
-        let circle_data = CircleData::try_from(circle.clone())?;
-        let circle_query =
-            sqlx::query("INSERT INTO circles (id, name, owner_id, capacity) VALUES (?, ?, ?, ?)")
-                .bind(circle_data.id.as_str())
-                .bind(circle_data.name)
-                .bind(circle_data.owner_id)
-                .bind(circle_data.capacity);
+#[derive(serde::Deserialize, serde::Serialize)]
+struct HistoryData {
+    member_id: String,
+    change: String,
+    // Unix seconds.
+    at: i64,
+    sequence: u64,
+}
+
+impl std::convert::From<Circle> for CircleData {
+    fn from(circle: Circle) -> Self {
+        CircleData {
+            id: circle.id.into(),
+            name: circle.name,
+            owner: MemberData::from(circle.owner),
+            capacity: circle.capacity.into(),
+            members: circle.members.into_iter().map(MemberData::from).collect(),
+            deleted_at: None,
+            created_at: circle.created_at.map(Timestamp::unix_seconds),
+            removed: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+}
 
-        circle_query.execute(&self.db).await.map_err(|e| {
-            eprintln!("Failed to insert circle: {:?}", e);
-            anyhow::Error::msg("Failed to insert circle")
-        })?;
+impl std::convert::TryFrom<CircleData> for Circle {
+    type Error = Error;
 
-        let owner_query = sqlx::query(
-            "INSERT INTO members (id, name, age, grade, major, circle_id) VALUES (?, ?, ?, ?, ?, ?)",
+    fn try_from(data: CircleData) -> Result<Self, Self::Error> {
+        let owner = Member::reconstruct(
+            MemberId::from_str(&data.owner.id)?,
+            data.owner.name,
+            data.owner.age,
+            Grade::try_from(data.owner.grade)?,
+            Major::from(data.owner.major.as_str()),
+            MemberRole::try_from(data.owner.role.as_str())?,
         );
+        let owner_id = owner.id.clone();
+        let members = std::iter::once(Ok(owner))
+            .chain(data.members.into_iter().map(Member::try_from))
+            .collect::<Result<Vec<Member>, Error>>()?;
+        let circle = Circle::reconstruct_from_rows(
+            CircleId::from_str(&data.id)?,
+            data.name,
+            &owner_id,
+            Capacity::try_from(data.capacity)?,
+            members,
+        )?;
+        Ok(match data.created_at {
+            Some(seconds) => circle.with_created_at(Timestamp::from_unix_seconds(seconds)),
+            None => circle,
+        })
+    }
+}
 
-        owner_query
-            .bind(circle_data.owner.id)
-            .bind(circle_data.owner.name)
-            .bind(circle_data.owner.age)
-            .bind(circle_data.owner.grade)
-            .bind(circle_data.owner.major)
-            .bind(circle_data.id.as_str())
-            .execute(&self.db)
-            .await
-            .map_err(|e| {
-                eprintln!("Failed to insert owner: {:?}", e);
-                anyhow::Error::msg("Failed to insert owner")
-            })?;
-
-        if circle_data.members.is_empty() {
-            return Ok(());
-        }
-        Ok(())
-    }
-
-    async fn update(&self, circle: &Circle) -> Result<Circle, anyhow::Error> {
-        tracing::info!("update_circle : {:?}", circle);
-        let circle_data = CircleData::try_from(circle.clone())?;
-        let circle_query =
-            sqlx::query("UPDATE circles SET name = ?, owner_id = ?, capacity = ? WHERE id = ?")
-                .bind(circle_data.name)
-                .bind(circle_data.owner_id)
-                .bind(circle_data.capacity)
-                .bind(circle_data.id);
-
-        circle_query.execute(&self.db).await.map_err(|e| {
-            eprintln!("Failed to update circle: {:?}", e);
-            anyhow::Error::msg("Failed to update circle")
-        })?;
-
-        // let member_query =
-        //     sqlx::query("DELETE FROM members WHERE circle_id = ?").bind(circle_data.id);
-        // member_query.execute(&self.db).await.map_err(|e| {
-        //     eprintln!("Failed to delete members: {:?}", e);
-        //     anyhow::Error::msg("Failed to delete members")
-        // })?;
-
-        // for member in circle_data.members {
-        //     let member_query = sqlx::query(
-        //         "INSERT INTO members (name, age, grade, major, circle_id) VALUES (?, ?, ?, ?, ?, ?)",
-        //     );
-        //     member_query
-        //         .bind(member.name)
-        //         .bind(member.age)
-        //         .bind(member.grade)
-        //         .bind(member.major)
-        //         .bind(circle_data.id)
-        //         .execute(&self.db)
-        //         .await
-        //         .map_err(|e| {
-        //             eprintln!("Failed to insert member: {:?}", e);
-        //             anyhow::Error::msg("Failed to insert member")
-        //         })?;
-        // }
-
-        Ok(circle.clone())
-    }
-
-    async fn delete(&self, _circle: &Circle) -> Result<(), anyhow::Error> {
-        todo!()
+#[derive(serde::Deserialize, serde::Serialize)]
+struct MemberData {
+    id: String,
+    name: String,
+    age: i16,
+    grade: i16,
+    major: String,
+    role: String,
+}
+
+impl std::convert::From<Member> for MemberData {
+    fn from(value: Member) -> Self {
+        Self {
+            id: value.id.into(),
+            name: value.name,
+            age: value.age,
+            grade: value.grade.into(),
+            major: value.major.into(),
+            role: value.role.into(),
+        }
+    }
+}
+
+impl std::convert::TryFrom<MemberData> for Member {
+    type Error = Error;
+
+    fn try_from(value: MemberData) -> Result<Self, Self::Error> {
+        Ok(Member::reconstruct(
+            MemberId::from_str(value.id.as_str())?,
+            value.name,
+            value.age,
+            Grade::try_from(value.grade)?,
+            Major::from(value.major.as_str()),
+            MemberRole::try_from(value.role.as_str())?,
+        ))
     }
 }
 
 #[cfg(test)]
-mod tests {}
\ No newline at end of file
+mod tests {
+    use domain::{
+        aggregate::{
+            circle::Circle,
+            member::Member,
+            value_object::{
+                capacity::{Capacity, CapacityError},
+                circle_id::CircleId,
+                grade::Grade,
+                major::Major,
+                member_id::MemberId,
+                member_role::MemberRole,
+            },
+        },
+        error::DomainError,
+        interface::circle_repository_interface::{
+            CircleRepositoryInterface, ConflictError, MembershipChange, NotFoundError, Upserted,
+        },
+    };
+    use futures_util::TryStreamExt;
+
+    use super::CircleRepository;
+
+    #[tokio::test]
+    async fn test() -> anyhow::Result<()> {
+        let mut circle1 = build_circle()?;
+        let repository = CircleRepository::new();
+        assert!(repository.find_by_id(&circle1.id).await.is_err());
+        repository.create(&circle1).await?;
+        assert_eq!(repository.find_by_id(&circle1.id).await?, circle1);
+        circle1.name = "circle_name2".to_string();
+        repository.update(&circle1).await?;
+        assert_eq!(repository.find_by_id(&circle1.id).await?, circle1);
+        repository.delete(&circle1).await?;
+        assert!(repository.find_by_id(&circle1.id).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete_and_restore() -> anyhow::Result<()> {
+        let circle = build_circle()?;
+        let repository = CircleRepository::new();
+        repository.create(&circle).await?;
+
+        repository.delete(&circle).await?;
+        assert!(repository.find_by_id(&circle.id).await.is_err());
+        assert!(repository.find_all().await?.is_empty());
+        assert!(repository.delete(&circle).await.is_err());
+
+        repository.restore(&circle.id).await?;
+        assert_eq!(repository.find_by_id(&circle.id).await?, circle);
+        assert_eq!(repository.find_all().await?, vec![circle.clone()]);
+        assert!(repository.restore(&circle.id).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_archived() -> anyhow::Result<()> {
+        let archived = build_circle()?;
+        let kept = build_circle()?;
+        let repository = CircleRepository::new();
+        repository.create(&archived).await?;
+        repository.create(&kept).await?;
+
+        repository.delete(&archived).await?;
+        let found = repository.find_archived().await?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].circle, archived);
+        assert!(found[0].deleted_at >= archived.created_at.expect("created_at is set"));
+
+        repository.restore(&archived.id).await?;
+        assert!(repository.find_archived().await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upsert_by_name() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let mut circle = build_circle()?;
+        circle.capacity = Capacity::new(5)?;
+        circle.add_member(Member::new(
+            "member".to_string(),
+            19,
+            Grade::First,
+            Major::Art,
+        ))?;
+        assert_eq!(
+            repository.upsert_by_name(&circle).await?,
+            Upserted::Created(circle.clone())
+        );
+
+        let mut same_name = build_circle()?;
+        same_name.name = circle.name.clone();
+        same_name.capacity = Capacity::new(8)?;
+        let Upserted::Updated(updated) = repository.upsert_by_name(&same_name).await? else {
+            panic!("an existing name should be updated");
+        };
+        assert_eq!(updated.id, circle.id);
+        assert_eq!(updated.members, circle.members);
+        assert_eq!(i16::from(updated.capacity), 8);
+        assert_eq!(repository.find_by_id(&circle.id).await?, updated);
+        assert_eq!(repository.count_circles().await?, 1);
+
+        repository.delete(&updated).await?;
+        let error = repository.upsert_by_name(&same_name).await.unwrap_err();
+        assert!(error.is::<ConflictError>());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_adjust_capacity() -> anyhow::Result<()> {
+        let member = |name: &str| Member::new(name.to_string(), 19, Grade::First, Major::Art);
+        let circle = build_circle()?;
+        let repository = CircleRepository::new();
+        repository.create(&circle).await?;
+
+        assert_eq!(repository.adjust_capacity(&circle.id, 2).await?, 5);
+        let mut full = repository.find_by_id(&circle.id).await?;
+        for name in [
+            "member_name2",
+            "member_name3",
+            "member_name4",
+            "member_name5",
+        ] {
+            full.add_member(member(name))?;
+        }
+        repository.update(&full).await?;
+
+        let error = repository
+            .adjust_capacity(&circle.id, -1)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<DomainError>(),
+            Some(DomainError::Capacity(CapacityError::BelowMembership {
+                capacity: 4,
+                members: 5
+            }))
+        ));
+        assert_eq!(repository.find_by_id(&circle.id).await?, full);
+        assert!(repository
+            .adjust_capacity(&CircleId::gen(), 1)
+            .await
+            .unwrap_err()
+            .is::<NotFoundError>());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_many() -> anyhow::Result<()> {
+        let circles = vec![build_circle()?, build_circle()?, build_circle()?];
+        let repository = CircleRepository::new();
+        repository.create_many(&circles).await?;
+        for circle in &circles {
+            assert_eq!(&repository.find_by_id(&circle.id).await?, circle);
+        }
+
+        let fresh = build_circle()?;
+        let batch = vec![fresh.clone(), circles[0].clone()];
+        assert!(repository.create_many(&batch).await.is_err());
+        assert!(repository.find_by_id(&fresh.id).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_with_transaction_rolls_back() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let mut existing = build_circle()?;
+        repository.create(&existing).await?;
+
+        let fresh = build_circle()?;
+        let missing = build_circle()?;
+        existing.name = "renamed".to_string();
+        let result = repository
+            .with_transaction(|transaction| {
+                transaction.create(&fresh);
+                transaction.update(&existing);
+                transaction.update(&missing);
+                Ok(())
+            })
+            .await;
+        assert!(result.is_err());
+        assert!(repository.find_by_id(&fresh.id).await.is_err());
+        assert_ne!(repository.find_by_id(&existing.id).await?.name, "renamed");
+
+        let result = repository
+            .with_transaction(|transaction| -> anyhow::Result<()> {
+                transaction.create(&fresh);
+                anyhow::bail!("abort")
+            })
+            .await;
+        assert!(result.is_err());
+        assert!(repository.find_by_id(&fresh.id).await.is_err());
+
+        repository
+            .with_transaction(|transaction| {
+                transaction.create(&fresh);
+                transaction.update(&existing);
+                Ok(())
+            })
+            .await?;
+        assert_eq!(repository.find_by_id(&fresh.id).await?, fresh);
+        assert_eq!(repository.find_by_id(&existing.id).await?, existing);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rollback_only_transaction() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let existing = build_circle()?;
+        repository.create(&existing).await?;
+
+        let fresh = build_circle()?;
+        repository
+            .with_transaction(|transaction| {
+                transaction.create(&fresh);
+                transaction.set_rollback_only();
+                Ok(())
+            })
+            .await?;
+        assert!(repository.find_by_id(&fresh.id).await.is_err());
+
+        let mut duplicate = build_circle()?;
+        duplicate.name = existing.name.clone();
+        let error = repository
+            .with_transaction(|transaction| {
+                transaction.create(&duplicate);
+                transaction.set_rollback_only();
+                Ok(())
+            })
+            .await
+            .unwrap_err();
+        assert!(error.is::<ConflictError>());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_set_members() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let mut circle = build_circle()?;
+        let kept = Member::new("kept".to_string(), 19, Grade::First, Major::Art);
+        let dropped = Member::new("dropped".to_string(), 19, Grade::First, Major::Art);
+        circle.add_member(kept.clone())?;
+        circle.add_member(dropped.clone())?;
+        repository.create(&circle).await?;
+
+        let added = Member::new("added".to_string(), 20, Grade::Second, Major::Law);
+        repository
+            .set_members(&circle.id, vec![kept.clone(), added.clone()])
+            .await?;
+        let stored = repository.find_by_id(&circle.id).await?;
+        assert_eq!(stored.owner, circle.owner);
+        assert_eq!(stored.members, vec![kept, added]);
+        assert!(repository.find_by_member_id(&dropped.id).await.is_err());
+
+        assert!(repository
+            .set_members(&CircleId::gen(), vec![])
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_removed_members() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let mut circle = build_circle()?;
+        let kept = Member::new("kept".to_string(), 19, Grade::First, Major::Art);
+        let dropped = Member::new("dropped".to_string(), 19, Grade::First, Major::Art);
+        circle.add_member(kept.clone())?;
+        circle.add_member(dropped.clone())?;
+        repository.create(&circle).await?;
+        assert!(repository
+            .find_removed_members(&circle.id)
+            .await?
+            .is_empty());
+
+        repository
+            .set_members(&circle.id, vec![kept.clone()])
+            .await?;
+        let removed = repository.find_removed_members(&circle.id).await?;
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].member, dropped);
+
+        repository
+            .set_members(&circle.id, vec![kept, dropped])
+            .await?;
+        assert!(repository
+            .find_removed_members(&circle.id)
+            .await?
+            .is_empty());
+        assert!(repository
+            .find_removed_members(&CircleId::gen())
+            .await?
+            .is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_members() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let circle = build_circle()?;
+        repository.create(&circle).await?;
+
+        let first = Member::new("first".to_string(), 19, Grade::First, Major::Art);
+        let second = Member::new("second".to_string(), 19, Grade::First, Major::Art);
+        let third = Member::new("third".to_string(), 19, Grade::First, Major::Art);
+        repository
+            .add_members(&circle.id, &[first.clone(), second.clone()])
+            .await?;
+        let error = repository
+            .add_members(&circle.id, &[third])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<DomainError>(),
+            Some(&DomainError::CapacityExceeded)
+        );
+        assert_eq!(
+            repository.find_by_id(&circle.id).await?.members,
+            vec![first, second]
+        );
+
+        let error = repository
+            .add_members(&CircleId::gen(), &[])
+            .await
+            .unwrap_err();
+        assert!(error.is::<NotFoundError>());
+        Ok(())
+    }
+
+    // A move is a leave from one circle and a join to the other, so the
+    // member's history reads across both.
+    #[tokio::test]
+    async fn test_find_membership_history() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let first = build_circle()?;
+        let second = build_circle()?;
+        repository.create(&first).await?;
+        repository.create(&second).await?;
+        let member = Member::new("member".to_string(), 19, Grade::First, Major::Art);
+
+        repository
+            .add_members(&first.id, std::slice::from_ref(&member))
+            .await?;
+        repository.set_members(&first.id, vec![]).await?;
+        repository
+            .add_members(&second.id, std::slice::from_ref(&member))
+            .await?;
+
+        let history = repository
+            .find_membership_history(&member.id)
+            .await?
+            .into_iter()
+            .map(|event| (event.circle_id, event.change))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            history,
+            vec![
+                (first.id.clone(), MembershipChange::Joined),
+                (first.id.clone(), MembershipChange::Left),
+                (second.id.clone(), MembershipChange::Joined),
+            ]
+        );
+        let owner_history = repository.find_membership_history(&first.owner.id).await?;
+        assert_eq!(owner_history.len(), 1);
+        assert!(repository
+            .find_membership_history(&MemberId::gen())
+            .await?
+            .is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_member_roles_round_trip() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let mut circle = build_circle()?;
+        let officer = Member::new("officer".to_string(), 19, Grade::First, Major::Art);
+        circle.add_member(officer.clone())?;
+        circle.assign_role(&officer.id, MemberRole::Officer)?;
+        repository.create(&circle).await?;
+
+        let stored = repository.find_by_id(&circle.id).await?;
+        assert_eq!(stored.owner.role, MemberRole::Owner);
+        assert_eq!(stored.members[0].role, MemberRole::Officer);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_name_conflicts() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let circle = build_circle()?;
+        repository.create(&circle).await?;
+
+        let mut duplicate = build_circle()?;
+        duplicate.name = circle.name.clone();
+        let error = repository.create(&duplicate).await.unwrap_err();
+        assert!(error.is::<ConflictError>());
+        assert!(repository.find_by_id(&duplicate.id).await.is_err());
+
+        let error = repository
+            .create_many(&[build_circle()?, duplicate])
+            .await
+            .unwrap_err();
+        assert!(error.is::<ConflictError>());
+
+        let mut renamed = build_circle()?;
+        repository.create(&renamed).await?;
+        renamed.name = circle.name.clone();
+        assert!(repository
+            .update(&renamed)
+            .await
+            .unwrap_err()
+            .is::<ConflictError>());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_by_member_id() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let mut circle = build_circle()?;
+        let member = Member::new("member_name2".to_string(), 19, Grade::First, Major::Music);
+        circle.add_member(member.clone())?;
+        repository.create(&circle).await?;
+        repository.create(&build_circle()?).await?;
+
+        assert_eq!(
+            repository.find_by_member_id(&circle.owner.id).await?,
+            circle
+        );
+        assert_eq!(repository.find_by_member_id(&member.id).await?, circle);
+        let error = repository
+            .find_by_member_id(&MemberId::gen())
+            .await
+            .unwrap_err();
+        assert!(error.is::<NotFoundError>());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_owned_by() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let first = build_circle()?;
+        let mut second = build_circle()?;
+        second.owner = first.owner.clone();
+        let other = build_circle()?;
+        for circle in [&first, &second, &other] {
+            repository.create(circle).await?;
+        }
+
+        let mut owned = repository.find_owned_by(&first.owner.id).await?;
+        owned.sort_by_key(|circle| circle.name.clone());
+        let mut expected = vec![first.clone(), second];
+        expected.sort_by_key(|circle| circle.name.clone());
+        assert_eq!(owned, expected);
+        assert!(repository.find_owned_by(&MemberId::gen()).await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_full_circles() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let mut full = build_circle()?;
+        for name in ["member_name2", "member_name3"] {
+            full.add_member(Member::new(
+                name.to_string(),
+                19,
+                Grade::First,
+                Major::Music,
+            ))?;
+        }
+        let open = build_circle()?;
+        repository.create(&full).await?;
+        repository.create(&open).await?;
+
+        assert_eq!(repository.find_full_circles().await?, vec![full]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_by_owner_major() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let art = build_circle()?;
+        let mut music = build_circle()?;
+        music.owner.major = Major::Music;
+        // A member's major does not count, only the owner's.
+        music.add_member(Member::new(
+            "member_name2".to_string(),
+            19,
+            Grade::First,
+            Major::Art,
+        ))?;
+        repository.create(&art).await?;
+        repository.create(&music).await?;
+
+        assert_eq!(repository.find_by_owner_major(Major::Art).await?, vec![art]);
+        assert_eq!(
+            repository.find_by_owner_major(Major::Music).await?,
+            vec![music]
+        );
+        assert!(repository.find_by_owner_major(Major::Law).await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_many() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let member = Member::new("member_name2".to_string(), 19, Grade::First, Major::Music);
+        let mut from = build_circle()?;
+        from.add_member(member.clone())?;
+        let mut to = build_circle()?;
+        repository.create(&from).await?;
+        repository.create(&to).await?;
+
+        from.remove_member(&member)?;
+        to.add_member(member.clone())?;
+        repository.update_many(&[from.clone(), to.clone()]).await?;
+        assert_eq!(repository.find_by_id(&from.id).await?, from);
+        assert_eq!(repository.find_by_id(&to.id).await?, to);
+
+        let missing = build_circle()?;
+        from.name = "Renamed club".to_string();
+        assert!(repository
+            .update_many(&[from.clone(), missing])
+            .await
+            .is_err());
+        assert_ne!(repository.find_by_id(&from.id).await?.name, from.name);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_page() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let mut circles = Vec::new();
+        for _ in 0..5 {
+            let circle = build_circle()?;
+            repository.create(&circle).await?;
+            circles.push(circle);
+        }
+        circles.sort_by_key(|circle| circle.id.to_string());
+
+        let (page, total) = repository.find_page(2, 1).await?;
+        assert_eq!(total, 5);
+        assert_eq!(page, circles[1..3].to_vec());
+
+        let (page, total) = repository.find_page(10, 4).await?;
+        assert_eq!(total, 5);
+        assert_eq!(page, circles[4..].to_vec());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stream_all() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let mut circles = Vec::new();
+        for _ in 0..3 {
+            let circle = build_circle()?;
+            repository.create(&circle).await?;
+            circles.push(circle);
+        }
+        let deleted = circles.pop().unwrap();
+        repository.delete(&deleted).await?;
+
+        let mut streamed = repository.stream_all().try_collect::<Vec<Circle>>().await?;
+        streamed.sort_by_key(|circle| circle.id.to_string());
+        circles.sort_by_key(|circle| circle.id.to_string());
+        assert_eq!(streamed, circles);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_after() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let mut circles = Vec::new();
+        for _ in 0..5 {
+            let circle = build_circle()?;
+            repository.create(&circle).await?;
+            circles.push(circle);
+        }
+        circles.sort_by_key(|circle| circle.id.to_string());
+
+        let page = repository.find_after(&circles[1].id, 2).await?;
+        assert_eq!(page, circles[2..4].to_vec());
+
+        let page = repository.find_after(&circles[3].id, 10).await?;
+        assert_eq!(page, circles[4..].to_vec());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_by_ids() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let first = build_circle()?;
+        let second = build_circle()?;
+        repository.create(&first).await?;
+        repository.create(&second).await?;
+        let missing = build_circle()?;
+
+        let found = repository
+            .find_by_ids(&[
+                second.id.clone(),
+                missing.id.clone(),
+                first.id.clone(),
+                second.id.clone(),
+            ])
+            .await?;
+        assert_eq!(found, vec![second, first]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_counts() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let mut circle1 = build_circle()?;
+        circle1.add_member(Member::new(
+            "member_name2".to_string(),
+            19,
+            Grade::First,
+            Major::Music,
+        ))?;
+        let circle2 = build_circle()?;
+        repository.create(&circle1).await?;
+        repository.create(&circle2).await?;
+
+        assert_eq!(repository.count_circles().await?, 2);
+        assert_eq!(repository.count_members().await?, 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_by_name_like() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let mut music = build_circle()?;
+        music.name = "Music club".to_string();
+        let mut percent = build_circle()?;
+        percent.name = "100% Fun".to_string();
+        let mut plain = build_circle()?;
+        plain.name = "100 Fun".to_string();
+        for circle in [&music, &percent, &plain] {
+            repository.create(circle).await?;
+        }
+
+        let partial = repository.find_by_name_like("SIC").await?;
+        assert_eq!(partial, vec![music.clone()]);
+
+        let exact = repository.find_by_name_like("Music club").await?;
+        assert_eq!(exact, vec![music]);
+
+        let literal = repository.find_by_name_like("100%").await?;
+        assert_eq!(literal, vec![percent]);
+        Ok(())
+    }
+
+    // Names must be unique, so each call gets its own.
+    fn build_circle() -> anyhow::Result<Circle> {
+        Ok(Circle::new(
+            format!("Music club {}", CircleId::gen()),
+            Member::new("member_name1".to_string(), 21, Grade::Third, Major::Art),
+            Capacity::new(3)?,
+        )?)
+    }
+}
@@ -1,185 +1,1520 @@
-use std::str::FromStr;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
 
-use anyhow::Error;
 use domain::{
     aggregate::{
         circle::Circle,
         member::Member,
-        value_object::{circle_id::CircleId, grade::Grade, major::Major, member_id::MemberId},
+        value_object::{
+            capacity::{Capacity, CapacityError},
+            circle_id::CircleId,
+            major::Major,
+            member_id::MemberId,
+            timestamp::Timestamp,
+        },
     },
-    interface::circle_repository_interface::CircleRepositoryInterface,
+    error::DomainError,
+    interface::circle_repository_interface::{
+        ArchivedCircle, CircleRepositoryInterface, CircleTransaction, CircleWrite, ConflictError,
+        DataIntegrityError, MembershipChange, MembershipEvent, NotFoundError, RemovedMember,
+        RepositoryError, UnavailableError, Upserted,
+    },
+};
+use futures_util::{Stream, StreamExt};
+use sqlx::{
+    mysql::{MySqlConnection, MySqlDatabaseError, MySqlQueryResult, MySqlRow},
+    Executor, MySql, Row, Transaction,
 };
 
-use super::db::Db;
+use super::{
+    db_data::{circle_data::CircleData, member_data::MemberData},
+    deadline,
+    retry::{self, RetryPolicy},
+    slow_query,
+};
+
+// Every query that feeds `hydrate` selects from `circles` through this, so
+// `created_at` arrives as Unix seconds and never depends on the session
+// timezone.
+macro_rules! select_circles {
+    ($rest:literal) => {
+        concat!(
+            "SELECT circles.*, CAST(UNIX_TIMESTAMP(circles.created_at) AS SIGNED) AS created_at_unix \
+             FROM circles ",
+            $rest
+        )
+    };
+}
+
+// Circles read ahead of a `stream_all` consumer.
+const STREAM_BUFFER: usize = 16;
+
+enum AddedMembers {
+    Added,
+    Missing,
+    Refused { members: usize },
+}
+
+enum ReplacedMembers {
+    Replaced,
+    Missing,
+    Refused(anyhow::Error),
+}
+
+enum CapacityDelta {
+    Adjusted(i16),
+    Missing,
+    Unchanged { capacity: i16, members: usize },
+}
+
+enum UpsertRow {
+    Inserted,
+    Updated(String),
+    Deleted,
+    BelowMembership { members: usize },
+}
 
 #[derive(Clone, Debug)]
-pub struct CircleRepository {
-    db: Db,
+pub struct CircleRepositoryWithMySql {
+    db: sqlx::MySqlPool,
+    retry: RetryPolicy,
+    slow_query_threshold: Option<Duration>,
 }
 
-impl CircleRepository {
-    pub fn new() -> Self {
-        Self { db: Db::new() }
+impl CircleRepositoryWithMySql {
+    pub fn new(db: sqlx::MySqlPool) -> Self {
+        Self {
+            db,
+            retry: RetryPolicy::default(),
+            slow_query_threshold: None,
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
     }
+
+    pub fn with_slow_query_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_query_threshold = Some(threshold);
+        self
+    }
+
+    // Every trait method runs its queries through here, so the request
+    // deadline applies and anything slower than the threshold is logged
+    // under the method's name.
+    async fn run<T, F>(&self, operation: &'static str, future: F) -> Result<T, anyhow::Error>
+    where
+        F: Future<Output = Result<T, anyhow::Error>>,
+    {
+        slow_query::logged(
+            self.slow_query_threshold,
+            operation,
+            deadline::bounded(future),
+        )
+        .await
+    }
+
+    // Not wrapped in `run`, so methods that already are can read a circle back
+    // without being timed twice.
+    async fn load(&self, circle_id: &CircleId) -> Result<Circle, anyhow::Error> {
+        // `circles.id` is VARCHAR(36), numeric ids included, so the string
+        // form is the matching bind; a number would make MySQL cast every
+        // stored id to compare and skip the primary key.
+        let circle_query = sqlx::query(select_circles!("WHERE id = ? AND deleted_at IS NULL"))
+            .bind(String::from(circle_id.clone()));
+
+        let circle_row = circle_query
+            .fetch_one(&self.db)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch circle by id"))?;
+
+        self.hydrate(circle_row).await
+    }
+
+    async fn hydrate(&self, circle_row: MySqlRow) -> Result<Circle, anyhow::Error> {
+        let member_query =
+            sqlx::query("SELECT * FROM members WHERE circle_id = ? AND deleted_at IS NULL")
+                .bind(circle_row.get::<String, _>("id"));
+
+        let members_row = member_query
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch members by circle id"))?;
+
+        let members = members_row.iter().map(member_data).collect();
+        assemble(&circle_row, members)
+    }
+}
+
+fn member_data(member: &MySqlRow) -> MemberData {
+    MemberData {
+        id: member.get::<String, _>("id"),
+        name: member.get::<String, _>("name"),
+        age: member.get::<i16, _>("age"),
+        grade: member.get::<i16, _>("grade"),
+        major: member.get::<String, _>("major"),
+        role: member.get::<String, _>("role"),
+    }
+}
+
+// A member read off a `circles LEFT JOIN members` row, whose columns are
+// aliased so they don't shadow the circle's. `None` for a circle with no
+// live members left.
+fn joined_member_data(row: &MySqlRow) -> Option<MemberData> {
+    Some(MemberData {
+        id: row.get::<Option<String>, _>("member_id")?,
+        name: row.get::<String, _>("member_name"),
+        age: row.get::<i16, _>("member_age"),
+        grade: row.get::<i16, _>("member_grade"),
+        major: row.get::<String, _>("member_major"),
+        role: row.get::<String, _>("member_role"),
+    })
+}
+
+fn assemble(circle_row: &MySqlRow, members: Vec<MemberData>) -> Result<Circle, anyhow::Error> {
+    let circle_id = circle_row.get::<String, _>("id");
+    let owner_id = circle_row.get::<String, _>("owner_id");
+    let owner: MemberData = members
+        .iter()
+        .find(|member| member.id == owner_id)
+        .ok_or_else(|| {
+            let error = DataIntegrityError::missing_owner(circle_id.as_str(), owner_id.as_str());
+            tracing::error!("{}", error);
+            error
+        })?
+        .clone();
+
+    let circle_data = CircleData {
+        id: circle_id,
+        name: circle_row.get::<String, _>("name"),
+        owner_id,
+        owner,
+        capacity: circle_row.get::<i16, _>("capacity"),
+        members,
+        created_at: circle_row.get::<Option<i64>, _>("created_at_unix"),
+    };
+
+    Circle::try_from(circle_data)
 }
 
-impl CircleRepositoryInterface for CircleRepository {
-    async fn find_all(&self) -> Result<Vec<Circle>, Error> {
-        todo!("Implement this method")
+impl CircleRepositoryWithMySql {
+    // The count and the page are read in one transaction so `total` matches
+    // the rows the page was cut from.
+    async fn read_page(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<MySqlRow>, i64), sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let total = sqlx::query("SELECT COUNT(*) AS count FROM circles WHERE deleted_at IS NULL")
+            .fetch_one(&mut *tx)
+            .await?
+            .get::<i64, _>("count");
+        let circle_rows = sqlx::query(select_circles!(
+            "WHERE deleted_at IS NULL ORDER BY id LIMIT ? OFFSET ?"
+        ))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok((circle_rows, total))
     }
 
-    async fn find_by_id(&self, circle_id: &CircleId) -> Result<Circle, Error> {
-        match self.db.get::<CircleData, _>(&circle_id.to_string())? {
-            Some(data) => Ok(Circle::try_from(data)?),
-            None => Err(Error::msg("Circle not found")),
+    // The archived copies go in the same transaction that brings the circle
+    // back. `false` when there was no deleted circle to restore, or when its
+    // owner has since gone to another circle, as a merge does.
+    async fn write_restore(&self, circle_id: &str) -> Result<bool, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let result = sqlx::query(
+            "UPDATE circles SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL \
+             AND owner_id IN (SELECT id FROM members WHERE circle_id = ? AND deleted_at IS NULL)",
+        )
+        .bind(circle_id)
+        .bind(circle_id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(false);
         }
+        sqlx::query("DELETE FROM members_archive WHERE circle_id = ?")
+            .bind(circle_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM circles_archive WHERE id = ?")
+            .bind(circle_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(true)
     }
 
-    async fn create(&self, circle: &Circle) -> Result<(), Error> {
-        match self.db.get::<CircleData, _>(&circle.id.to_string())? {
-            Some(_) => Err(Error::msg("Circle already exists")),
-            None => {
-                self.db
-                    .set(circle.id.to_string(), &CircleData::from(circle.clone()))?;
-                Ok(())
+    // `FOR UPDATE` holds the circle row until commit, so a concurrent add
+    // waits here and then counts the rows this one inserted. The limit is the
+    // capacity, or `Circle::MAX_MEMBERS` if that is lower.
+    async fn write_add_members(
+        &self,
+        circle_id: &str,
+        members: &[MemberData],
+    ) -> Result<AddedMembers, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let row = sqlx::query(
+            "SELECT capacity, \
+             (SELECT COUNT(*) FROM members WHERE members.circle_id = circles.id AND members.deleted_at IS NULL) AS members \
+             FROM circles WHERE id = ? AND deleted_at IS NULL FOR UPDATE",
+        )
+        .bind(circle_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(row) = row else {
+            tx.rollback().await?;
+            return Ok(AddedMembers::Missing);
+        };
+        let capacity = row.get::<i16, _>("capacity") as usize;
+        let current = row.get::<i64, _>("members") as usize;
+        if current + members.len() > capacity.min(Circle::MAX_MEMBERS) {
+            tx.rollback().await?;
+            return Ok(AddedMembers::Refused { members: current });
+        }
+        for member in members {
+            insert_member_row(&mut tx, member, circle_id, false).await?;
+        }
+        tx.commit().await?;
+        Ok(AddedMembers::Added)
+    }
+
+    // The circle row is locked before the roster is read, so the diff is taken
+    // against the members as they stand at commit and a concurrent add or set
+    // waits for this one rather than being overwritten by it.
+    async fn write_set_members(
+        &self,
+        circle_id: &str,
+        members: &[Member],
+    ) -> Result<ReplacedMembers, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let circle_row = sqlx::query(select_circles!(
+            "WHERE id = ? AND deleted_at IS NULL FOR UPDATE"
+        ))
+        .bind(circle_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(circle_row) = circle_row else {
+            tx.rollback().await?;
+            return Ok(ReplacedMembers::Missing);
+        };
+        let member_rows =
+            sqlx::query("SELECT * FROM members WHERE circle_id = ? AND deleted_at IS NULL")
+                .bind(circle_id)
+                .fetch_all(&mut *tx)
+                .await?;
+        let replaced = assemble(&circle_row, member_rows.iter().map(member_data).collect())
+            .and_then(|mut circle| {
+                circle.replace_members(members.to_vec())?;
+                Ok(circle)
+            });
+        let circle = match replaced {
+            Ok(circle) => circle,
+            Err(error) => {
+                tx.rollback().await?;
+                return Ok(ReplacedMembers::Refused(error));
             }
+        };
+        update_circle_rows(&mut tx, &CircleData::from(circle)).await?;
+        tx.commit().await?;
+        Ok(ReplacedMembers::Replaced)
+    }
+
+    // The guards repeat `Capacity`'s range and `Circle::change_capacity`, so
+    // the row is only written when the new capacity would be accepted. When
+    // nothing matched, the row is read back under the same lock to tell a
+    // missing circle from a refused capacity.
+    async fn write_capacity_delta(
+        &self,
+        circle_id: &str,
+        delta: i16,
+    ) -> Result<CapacityDelta, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let result = sqlx::query(
+            "UPDATE circles SET capacity = capacity + ? \
+             WHERE id = ? AND deleted_at IS NULL \
+             AND capacity + ? BETWEEN ? AND ? \
+             AND capacity + ? >= (SELECT COUNT(*) FROM members WHERE members.circle_id = circles.id AND members.deleted_at IS NULL)",
+        )
+        .bind(delta)
+        .bind(circle_id)
+        .bind(delta)
+        .bind(Capacity::MIN)
+        .bind(Capacity::MAX)
+        .bind(delta)
+        .execute(&mut *tx)
+        .await?;
+        let row = sqlx::query(
+            "SELECT capacity, \
+             (SELECT COUNT(*) FROM members WHERE members.circle_id = circles.id AND members.deleted_at IS NULL) AS members \
+             FROM circles WHERE id = ? AND deleted_at IS NULL FOR UPDATE",
+        )
+        .bind(circle_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(match row {
+            None => CapacityDelta::Missing,
+            Some(row) if result.rows_affected() > 0 => {
+                CapacityDelta::Adjusted(row.get::<i16, _>("capacity"))
+            }
+            Some(row) => CapacityDelta::Unchanged {
+                capacity: row.get::<i16, _>("capacity"),
+                members: row.get::<i64, _>("members") as usize,
+            },
+        })
+    }
+
+    // The row holding the name is read back under the same lock, and its id
+    // tells an insert from an update; the affected-rows count can't, since
+    // sqlx asks for found rows and an update to the same capacity then counts
+    // as 1, just like an insert. Anything but a committed
+    // insert or update leaves the transaction to roll back.
+    async fn write_upsert(&self, circle_data: &CircleData) -> Result<UpsertRow, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        sqlx::query(
+            "INSERT INTO circles (id, name, owner_id, capacity, created_at) \
+             VALUES (?, ?, ?, ?, COALESCE(FROM_UNIXTIME(?), CURRENT_TIMESTAMP)) \
+             ON DUPLICATE KEY UPDATE capacity = VALUES(capacity)",
+        )
+        .bind(circle_data.id.as_str())
+        .bind(circle_data.name.as_str())
+        .bind(circle_data.owner_id.as_str())
+        .bind(circle_data.capacity)
+        .bind(circle_data.created_at)
+        .execute(&mut *tx)
+        .await?;
+        let row = sqlx::query(
+            "SELECT id, CAST(deleted_at IS NOT NULL AS SIGNED) AS deleted, \
+             (SELECT COUNT(*) FROM members WHERE members.circle_id = circles.id AND members.deleted_at IS NULL) AS members \
+             FROM circles WHERE name = ? FOR UPDATE",
+        )
+        .bind(circle_data.name.as_str())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let id = row.get::<String, _>("id");
+        if id == circle_data.id {
+            insert_member_row(&mut tx, &circle_data.owner, &circle_data.id, true).await?;
+            for member in &circle_data.members {
+                insert_member_row(&mut tx, member, &circle_data.id, false).await?;
+            }
+            tx.commit().await?;
+            return Ok(UpsertRow::Inserted);
+        }
+        if row.get::<i64, _>("deleted") != 0 {
+            return Ok(UpsertRow::Deleted);
+        }
+        let members = row.get::<i64, _>("members") as usize;
+        if (circle_data.capacity as usize) < members {
+            return Ok(UpsertRow::BelowMembership { members });
         }
+        tx.commit().await?;
+        Ok(UpsertRow::Updated(id))
     }
 
-    async fn update(&self, circle: &Circle) -> Result<Circle, Error> {
-        match self.db.get::<CircleData, _>(&circle.id.to_string())? {
-            Some(_) => self
-                .db
-                .set(circle.id.to_string(), &CircleData::from(circle.clone()))
-                .and_then(|_| self.db.get::<CircleData, _>(&circle.id.to_string()))
-                .map(|data| match data {
-                    Some(data) => Circle::try_from(data),
-                    None => Err(Error::msg("Failed to convert circle data")),
-                })?,
-            None => Err(Error::msg("Circle not found")),
+    async fn write_update(&self, circles: &[CircleData]) -> Result<(), sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        for circle_data in circles {
+            update_circle_rows(&mut tx, circle_data).await?;
         }
+        retry::commit(tx).await
     }
 
-    async fn delete(&self, circle: &Circle) -> Result<(), Error> {
-        match self.db.get::<CircleData, _>(&circle.id.to_string())? {
-            Some(_) => self.db.remove(circle.id.to_string()),
-            None => Err(Error::msg("Circle not found")),
+    // `failed_at` is left on the index of the write being applied, so a
+    // failure can be reported against the circle that caused it.
+    async fn write_transaction(
+        &self,
+        writes: &[(CircleWrite, CircleData)],
+        rollback_only: bool,
+        failed_at: &AtomicUsize,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        for (index, (write, circle_data)) in writes.iter().enumerate() {
+            failed_at.store(index, Ordering::Relaxed);
+            match write {
+                CircleWrite::Create(_) => insert_circle_rows(&mut tx, circle_data).await?,
+                CircleWrite::Update(_) => update_circle_rows(&mut tx, circle_data).await?,
+                CircleWrite::Delete(_) => soft_delete_circle_row(&mut tx, circle_data).await?,
+            }
+        }
+        if rollback_only {
+            return tx.rollback().await;
         }
+        retry::commit(tx).await
     }
 }
 
-#[derive(serde::Deserialize, serde::Serialize)]
-struct CircleData {
-    id: String,
-    name: String,
-    owner: MemberData,
-    capacity: i16,
-    members: Vec<MemberData>,
+async fn insert_circle_rows(
+    tx: &mut Transaction<'_, MySql>,
+    circle_data: &CircleData,
+) -> Result<(), sqlx::Error> {
+    insert_circle_row(&mut **tx, circle_data).await?;
+    insert_member_row(tx, &circle_data.owner, &circle_data.id, true).await?;
+    for member in &circle_data.members {
+        insert_member_row(tx, member, &circle_data.id, false).await?;
+    }
+    Ok(())
+}
+
+// A soft delete keeps member rows so `restore` can bring them back; purging
+// the circle row removes them through ON DELETE CASCADE. The rows are also
+// copied to the archive tables as they stand once `deleted_at` is set.
+async fn soft_delete_circle_row(
+    tx: &mut Transaction<'_, MySql>,
+    circle_data: &CircleData,
+) -> Result<(), sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE circles SET deleted_at = CURRENT_TIMESTAMP WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(circle_data.id.as_str())
+    .execute(&mut **tx)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(sqlx::Error::RowNotFound);
+    }
+    sqlx::query(
+        "INSERT INTO circles_archive (id, name, capacity, owner_id, created_at, deleted_at) \
+         SELECT id, name, capacity, owner_id, created_at, deleted_at FROM circles WHERE id = ?",
+    )
+    .bind(circle_data.id.as_str())
+    .execute(&mut **tx)
+    .await?;
+    sqlx::query(
+        "INSERT INTO members_archive (id, circle_id, name, age, grade, major, role) \
+         SELECT id, circle_id, name, age, grade, major, role FROM members \
+         WHERE circle_id = ? AND deleted_at IS NULL",
+    )
+    .bind(circle_data.id.as_str())
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
 }
 
-impl std::convert::From<Circle> for CircleData {
-    fn from(circle: Circle) -> Self {
-        CircleData {
-            id: circle.id.into(),
-            name: circle.name,
-            owner: MemberData::from(circle.owner),
-            capacity: circle.capacity,
-            members: circle.members.into_iter().map(MemberData::from).collect(),
+// Rewrites the circle row and diffs its roster: rows for members no longer in
+// the aggregate are soft-deleted and the rest are upserted, so unchanged
+// members keep their ids.
+async fn update_circle_rows(
+    tx: &mut Transaction<'_, MySql>,
+    circle_data: &CircleData,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE circles SET name = ?, owner_id = ?, capacity = ? \
+         WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(circle_data.name.as_str())
+    .bind(circle_data.owner_id.as_str())
+    .bind(circle_data.capacity)
+    .bind(circle_data.id.as_str())
+    .execute(&mut **tx)
+    .await?;
+
+    let roster: Vec<&MemberData> = std::iter::once(&circle_data.owner)
+        .chain(circle_data.members.iter())
+        .collect();
+
+    let placeholders = vec!["?"; roster.len()].join(", ");
+    let dropped = format!(
+        "WHERE circle_id = ? AND deleted_at IS NULL AND id NOT IN ({})",
+        placeholders
+    );
+    let left_sql = format!(
+        "INSERT INTO membership_history (member_id, circle_id, event) \
+         SELECT id, circle_id, ? FROM members {}",
+        dropped
+    );
+    let delete_sql = format!(
+        "UPDATE members SET deleted_at = CURRENT_TIMESTAMP {}",
+        dropped
+    );
+    let mut left_query = sqlx::query(&left_sql)
+        .bind(MembershipChange::Left.as_str())
+        .bind(circle_data.id.as_str());
+    let mut delete_query = sqlx::query(&delete_sql).bind(circle_data.id.as_str());
+    for member in &roster {
+        left_query = left_query.bind(member.id.as_str());
+        delete_query = delete_query.bind(member.id.as_str());
+    }
+    left_query.execute(&mut **tx).await?;
+    delete_query.execute(&mut **tx).await?;
+
+    // A transfer hands `owner_of` to another row, so the old owner lets go of
+    // it first to keep the unique key satisfied.
+    sqlx::query("UPDATE members SET owner_of = NULL WHERE owner_of = ? AND id <> ?")
+        .bind(circle_data.id.as_str())
+        .bind(circle_data.owner_id.as_str())
+        .execute(&mut **tx)
+        .await?;
+
+    // Anyone not already live here is joining, and leaves the circle the
+    // upsert is about to move them out of, if any.
+    let present: HashSet<String> =
+        sqlx::query_scalar("SELECT id FROM members WHERE circle_id = ? AND deleted_at IS NULL")
+            .bind(circle_data.id.as_str())
+            .fetch_all(&mut **tx)
+            .await?
+            .into_iter()
+            .collect();
+    for member in roster {
+        let is_owner = member.id == circle_data.owner_id;
+        let joining = !present.contains(&member.id);
+        if joining {
+            sqlx::query(
+                "INSERT INTO membership_history (member_id, circle_id, event) \
+                 SELECT id, circle_id, ? FROM members WHERE id = ? AND deleted_at IS NULL",
+            )
+            .bind(MembershipChange::Left.as_str())
+            .bind(member.id.as_str())
+            .execute(&mut **tx)
+            .await?;
+        }
+        upsert_member_row(&mut **tx, member, &circle_data.id, is_owner).await?;
+        if joining {
+            insert_history_row(
+                &mut **tx,
+                &member.id,
+                &circle_data.id,
+                MembershipChange::Joined,
+            )
+            .await?;
         }
     }
+    Ok(())
 }
 
-impl std::convert::TryFrom<CircleData> for Circle {
-    type Error = Error;
+// `\` is MySQL's default LIKE escape character, so escaping it first keeps
+// user-supplied wildcards literal.
+fn escape_like(pattern: &str) -> String {
+    pattern
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
 
-    fn try_from(data: CircleData) -> Result<Self, Self::Error> {
-        Ok(Circle::reconstruct(
-            CircleId::from_str(&data.id)?,
-            data.name,
-            Member::reconstruct(
-                MemberId::from_str(&data.owner.id)?,
-                data.owner.name,
-                data.owner.age,
-                Grade::try_from(data.owner.grade)?,
-                Major::from(data.owner.major.as_str()),
-            ),
-            data.capacity,
-            data.members
-                .into_iter()
-                .map(Member::try_from)
-                .collect::<Result<Vec<Member>, Error>>()?,
-        ))
+// MySQL error number for a unique key violation.
+const DUPLICATE_KEY_ERROR_NUMBER: u16 = 1062;
+
+fn is_duplicate_key(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Database(e) => e
+            .try_downcast_ref::<MySqlDatabaseError>()
+            .is_some_and(|e| e.number() == DUPLICATE_KEY_ERROR_NUMBER),
+        _ => false,
     }
 }
 
-#[derive(serde::Deserialize, serde::Serialize)]
-struct MemberData {
-    id: String,
-    name: String,
-    age: i16,
-    grade: i16,
-    major: String,
+// The unique key on `circles.name`. MySQL names the key in the message, as
+// `for key 'uq_circles_name'` or, from 8.0, `'circles.uq_circles_name'`.
+const CIRCLE_NAME_KEY: &str = "uq_circles_name";
+
+fn names_circle_name_key(message: &str) -> bool {
+    message
+        .rsplit_once("for key ")
+        .is_some_and(|(_, key)| key.trim_matches('\'').rsplit('.').next() == Some(CIRCLE_NAME_KEY))
 }
 
-impl std::convert::From<Member> for MemberData {
-    fn from(value: Member) -> Self {
-        Self {
-            id: value.id.into(),
-            name: value.name,
-            age: value.age,
-            grade: value.grade.into(),
-            major: value.major.into(),
+fn is_duplicate_circle_name(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Database(e) => is_duplicate_key(error) && names_circle_name_key(e.message()),
+        _ => false,
+    }
+}
+
+// Gives callers a typed error to tell failures apart by, with the sqlx error
+// kept underneath as its source. A pool that can't hand out a connection in
+// time is an outage rather than a failed query, so clients can be asked to
+// retry; a missing row and a duplicate key get their own types, and anything
+// else is a `RepositoryError` carrying the database's code.
+fn query_error(error: sqlx::Error, message: &'static str) -> anyhow::Error {
+    match error {
+        sqlx::Error::PoolTimedOut => {
+            tracing::warn!("{}: no database connection available", message);
+            anyhow::Error::new(error).context(UnavailableError)
+        }
+        sqlx::Error::RowNotFound => anyhow::Error::new(error).context(NotFoundError::new(message)),
+        error if is_duplicate_key(&error) => {
+            anyhow::Error::new(error).context(ConflictError::new(message))
+        }
+        error => {
+            tracing::error!("{}: {:?}", message, error);
+            let code = match &error {
+                sqlx::Error::Database(e) => e.code().map(String::from),
+                _ => None,
+            };
+            anyhow::Error::new(error).context(RepositoryError {
+                message: message.to_string(),
+                code,
+            })
         }
     }
 }
 
-impl std::convert::TryFrom<MemberData> for Member {
-    type Error = Error;
+// Only the name key means the name is taken; a write also touches member,
+// history and archive rows, whose duplicates are reported as they are.
+fn circle_write_error(
+    error: sqlx::Error,
+    circle_data: &CircleData,
+    message: &'static str,
+) -> anyhow::Error {
+    if is_duplicate_circle_name(&error) {
+        return anyhow::Error::new(error).context(ConflictError::new(format!(
+            "Circle name '{}' is already taken",
+            circle_data.name
+        )));
+    }
+    query_error(error, message)
+}
 
-    fn try_from(value: MemberData) -> Result<Self, Self::Error> {
-        Ok(Member::reconstruct(
-            MemberId::from_str(value.id.as_str())?,
-            value.name,
-            value.age,
-            Grade::try_from(value.grade)?,
-            Major::from(value.major.as_str()),
-        ))
+async fn insert_circle_row<'c, E>(
+    executor: E,
+    circle_data: &CircleData,
+) -> Result<MySqlQueryResult, sqlx::Error>
+where
+    E: Executor<'c, Database = MySql>,
+{
+    sqlx::query(
+        "INSERT INTO circles (id, name, owner_id, capacity, created_at) \
+         VALUES (?, ?, ?, ?, COALESCE(FROM_UNIXTIME(?), CURRENT_TIMESTAMP))",
+    )
+    .bind(circle_data.id.as_str())
+    .bind(circle_data.name.as_str())
+    .bind(circle_data.owner_id.as_str())
+    .bind(circle_data.capacity)
+    .bind(circle_data.created_at)
+    .execute(executor)
+    .await
+}
+
+// A new row is always a member joining, so the history row goes with it.
+async fn insert_member_row(
+    conn: &mut MySqlConnection,
+    member_data: &MemberData,
+    circle_id: &str,
+    is_owner: bool,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    let result = sqlx::query(
+        "INSERT INTO members (id, name, age, grade, major, role, circle_id, owner_of) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(member_data.id.as_str())
+    .bind(member_data.name.as_str())
+    .bind(member_data.age)
+    .bind(member_data.grade)
+    .bind(member_data.major.as_str())
+    .bind(member_data.role.as_str())
+    .bind(circle_id)
+    .bind(is_owner.then_some(circle_id))
+    .execute(&mut *conn)
+    .await?;
+    insert_history_row(conn, &member_data.id, circle_id, MembershipChange::Joined).await?;
+    Ok(result)
+}
+
+async fn insert_history_row<'c, E>(
+    executor: E,
+    member_id: &str,
+    circle_id: &str,
+    change: MembershipChange,
+) -> Result<MySqlQueryResult, sqlx::Error>
+where
+    E: Executor<'c, Database = MySql>,
+{
+    sqlx::query("INSERT INTO membership_history (member_id, circle_id, event) VALUES (?, ?, ?)")
+        .bind(member_id)
+        .bind(circle_id)
+        .bind(change.as_str())
+        .execute(executor)
+        .await
+}
+
+async fn upsert_member_row<'c, E>(
+    executor: E,
+    member_data: &MemberData,
+    circle_id: &str,
+    is_owner: bool,
+) -> Result<MySqlQueryResult, sqlx::Error>
+where
+    E: Executor<'c, Database = MySql>,
+{
+    sqlx::query(
+        "INSERT INTO members (id, name, age, grade, major, role, circle_id, owner_of) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?) \
+         ON DUPLICATE KEY UPDATE name = VALUES(name), age = VALUES(age), grade = VALUES(grade), \
+         major = VALUES(major), role = VALUES(role), circle_id = VALUES(circle_id), \
+         owner_of = VALUES(owner_of), deleted_at = NULL",
+    )
+    .bind(member_data.id.as_str())
+    .bind(member_data.name.as_str())
+    .bind(member_data.age)
+    .bind(member_data.grade)
+    .bind(member_data.major.as_str())
+    .bind(member_data.role.as_str())
+    .bind(circle_id)
+    .bind(is_owner.then_some(circle_id))
+    .execute(executor)
+    .await
+}
+
+impl CircleRepositoryInterface for CircleRepositoryWithMySql {
+    async fn find_all(&self) -> Result<Vec<Circle>, anyhow::Error> {
+        self.run("find_all", async {
+            tracing::info!("find_all_circles");
+            let circle_query = sqlx::query(select_circles!("WHERE deleted_at IS NULL"));
+
+            let circle_rows = circle_query
+                .fetch_all(&self.db)
+                .await
+                .map_err(|e| query_error(e, "Failed to fetch circles"))?;
+
+            let mut circles = Vec::new();
+            for circle_row in circle_rows {
+                circles.push(self.hydrate(circle_row).await?);
+            }
+
+            Ok(circles)
+        })
+        .await
+    }
+
+    // The cursor runs on its own task and hands circles over a small channel,
+    // so only a few are held at once and a slow reader holds the query back
+    // instead of filling memory. Members come joined onto their circle's rows,
+    // which arrive together in `circles.id` order, so one query on one
+    // connection reads a consistent export and the stream never goes back to
+    // the pool while it holds a connection. The caller's deadline is carried
+    // over to the task, which is outside its scope. Dropping the stream drops
+    // the receiver, and the task stops at its next send.
+    fn stream_all(&self) -> impl Stream<Item = Result<Circle, anyhow::Error>> + Send + 'static {
+        let (sender, receiver) = tokio::sync::mpsc::channel(STREAM_BUFFER);
+        let repository = self.clone();
+        let caller_deadline = deadline::current();
+        tokio::spawn(async move {
+            let walk = repository.run("stream_all", async {
+                tracing::info!("stream_all_circles");
+                let mut rows = sqlx::query(
+                    "SELECT circles.*, \
+                     CAST(UNIX_TIMESTAMP(circles.created_at) AS SIGNED) AS created_at_unix, \
+                     members.id AS member_id, members.name AS member_name, \
+                     members.age AS member_age, members.grade AS member_grade, \
+                     members.major AS member_major, members.role AS member_role \
+                     FROM circles LEFT JOIN members \
+                     ON members.circle_id = circles.id AND members.deleted_at IS NULL \
+                     WHERE circles.deleted_at IS NULL ORDER BY circles.id",
+                )
+                .fetch(&repository.db);
+                let mut current: Option<(MySqlRow, Vec<MemberData>)> = None;
+                while let Some(row) = rows.next().await {
+                    let row = row.map_err(|e| query_error(e, "Failed to stream circles"))?;
+                    let member = joined_member_data(&row);
+                    let same_circle = current.as_ref().is_some_and(|(circle_row, _)| {
+                        circle_row.get::<String, _>("id") == row.get::<String, _>("id")
+                    });
+                    if !same_circle {
+                        if let Some((circle_row, members)) = current.take() {
+                            let circle = assemble(&circle_row, members)?;
+                            if sender.send(Ok(circle)).await.is_err() {
+                                return Ok(());
+                            }
+                        }
+                        current = Some((row, Vec::new()));
+                    }
+                    if let Some((_, members)) = current.as_mut() {
+                        members.extend(member);
+                    }
+                }
+                if let Some((circle_row, members)) = current {
+                    let _ = sender.send(assemble(&circle_row, members)).await;
+                }
+                Ok(())
+            });
+            let walked = match caller_deadline {
+                Some(at) => deadline::scope(at, walk).await,
+                None => walk.await,
+            };
+            if let Err(e) = walked {
+                let _ = sender.send(Err(e)).await;
+            }
+        });
+        futures_util::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|circle| (circle, receiver))
+        })
+    }
+
+    async fn find_page(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Circle>, i64), anyhow::Error> {
+        self.run("find_page", async {
+            tracing::info!("find_circle_page : limit {} offset {}", limit, offset);
+            let (circle_rows, total) = self
+                .read_page(limit, offset)
+                .await
+                .map_err(|e| query_error(e, "Failed to fetch circle page"))?;
+
+            let mut circles = Vec::new();
+            for circle_row in circle_rows {
+                circles.push(self.hydrate(circle_row).await?);
+            }
+
+            Ok((circles, total))
+        })
+        .await
+    }
+
+    async fn find_after(&self, after: &CircleId, limit: i64) -> Result<Vec<Circle>, anyhow::Error> {
+        self.run("find_after", async {
+            tracing::info!("find_circles_after : after {} limit {}", after, limit);
+            let circle_rows = sqlx::query(select_circles!(
+                "WHERE deleted_at IS NULL AND id > ? ORDER BY id LIMIT ?"
+            ))
+            .bind(after.to_string())
+            .bind(limit)
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch circles after cursor"))?;
+
+            let mut circles = Vec::new();
+            for circle_row in circle_rows {
+                circles.push(self.hydrate(circle_row).await?);
+            }
+
+            Ok(circles)
+        })
+        .await
+    }
+
+    // Two queries whatever the number of ids: the circles, then every member
+    // of all of them, grouped here by `circle_id`.
+    async fn find_by_ids(&self, ids: &[CircleId]) -> Result<Vec<Circle>, anyhow::Error> {
+        let mut seen = HashSet::new();
+        let ids: Vec<String> = ids
+            .iter()
+            .map(ToString::to_string)
+            .filter(|id| seen.insert(id.clone()))
+            .collect();
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.run("find_by_ids", async {
+            tracing::info!("find_circles_by_ids : {} ids", ids.len());
+            let placeholders = vec!["?"; ids.len()].join(", ");
+
+            let circle_sql = format!(
+                select_circles!("WHERE deleted_at IS NULL AND id IN ({})"),
+                placeholders
+            );
+            let mut circle_query = sqlx::query(&circle_sql);
+            for id in &ids {
+                circle_query = circle_query.bind(id);
+            }
+            let circle_rows = circle_query
+                .fetch_all(&self.db)
+                .await
+                .map_err(|e| query_error(e, "Failed to fetch circles by ids"))?;
+            if circle_rows.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let member_sql = format!(
+                "SELECT * FROM members WHERE deleted_at IS NULL AND circle_id IN ({})",
+                vec!["?"; circle_rows.len()].join(", ")
+            );
+            let mut member_query = sqlx::query(&member_sql);
+            for circle_row in &circle_rows {
+                member_query = member_query.bind(circle_row.get::<String, _>("id"));
+            }
+            let member_rows = member_query
+                .fetch_all(&self.db)
+                .await
+                .map_err(|e| query_error(e, "Failed to fetch members by circle ids"))?;
+
+            let mut members_by_circle: HashMap<String, Vec<MemberData>> = HashMap::new();
+            for member_row in &member_rows {
+                members_by_circle
+                    .entry(member_row.get::<String, _>("circle_id"))
+                    .or_default()
+                    .push(member_data(member_row));
+            }
+
+            let mut circles_by_id = HashMap::new();
+            for circle_row in &circle_rows {
+                let circle_id = circle_row.get::<String, _>("id");
+                let members = members_by_circle.remove(&circle_id).unwrap_or_default();
+                circles_by_id.insert(circle_id, assemble(circle_row, members)?);
+            }
+
+            Ok(ids
+                .iter()
+                .filter_map(|id| circles_by_id.remove(id))
+                .collect())
+        })
+        .await
+    }
+
+    async fn find_by_id(&self, circle_id: &CircleId) -> Result<Circle, anyhow::Error> {
+        self.run("find_by_id", async {
+            tracing::info!("find_circle_by_id : {:?}", circle_id);
+            self.load(circle_id).await
+        })
+        .await
+    }
+
+    async fn find_by_member_id(&self, member_id: &MemberId) -> Result<Circle, anyhow::Error> {
+        self.run("find_by_member_id", async {
+            tracing::info!("find_circle_by_member_id : {:?}", member_id);
+            let circle_query = sqlx::query(select_circles!(
+                "INNER JOIN members ON members.circle_id = circles.id \
+                 WHERE members.id = ? AND members.deleted_at IS NULL \
+                 AND circles.deleted_at IS NULL"
+            ))
+            .bind(member_id.to_string());
+
+            let circle_row = circle_query
+                .fetch_optional(&self.db)
+                .await
+                .map_err(|e| query_error(e, "Failed to fetch circle by member id"))?
+                .ok_or_else(|| NotFoundError::new("Circle not found for member"))?;
+
+            self.hydrate(circle_row).await
+        })
+        .await
+    }
+
+    async fn find_owned_by(&self, owner_id: &MemberId) -> Result<Vec<Circle>, anyhow::Error> {
+        self.run("find_owned_by", async {
+            tracing::info!("find_circles_owned_by : {:?}", owner_id);
+            let circle_query =
+                sqlx::query(select_circles!("WHERE owner_id = ? AND deleted_at IS NULL"))
+                    .bind(owner_id.to_string());
+
+            let circle_rows = circle_query
+                .fetch_all(&self.db)
+                .await
+                .map_err(|e| query_error(e, "Failed to fetch circles by owner id"))?;
+
+            let mut circles = Vec::new();
+            for circle_row in circle_rows {
+                circles.push(self.hydrate(circle_row).await?);
+            }
+
+            Ok(circles)
+        })
+        .await
+    }
+
+    async fn find_full_circles(&self) -> Result<Vec<Circle>, anyhow::Error> {
+        self.run("find_full_circles", async {
+            tracing::info!("find_full_circles");
+            // The owner has a members row too, so the count covers the whole roster.
+            let circle_query = sqlx::query(select_circles!(
+                "INNER JOIN members ON members.circle_id = circles.id \
+                 WHERE circles.deleted_at IS NULL AND members.deleted_at IS NULL \
+                 GROUP BY circles.id \
+                 HAVING COUNT(members.id) >= circles.capacity"
+            ));
+
+            let circle_rows = circle_query
+                .fetch_all(&self.db)
+                .await
+                .map_err(|e| query_error(e, "Failed to fetch full circles"))?;
+
+            let mut circles = Vec::new();
+            for circle_row in circle_rows {
+                circles.push(self.hydrate(circle_row).await?);
+            }
+
+            Ok(circles)
+        })
+        .await
+    }
+
+    async fn find_by_name_like(&self, pattern: &str) -> Result<Vec<Circle>, anyhow::Error> {
+        self.run("find_by_name_like", async {
+            tracing::info!("find_circles_by_name_like : {:?}", pattern);
+            let circle_query = sqlx::query(select_circles!(
+                "WHERE LOWER(name) LIKE LOWER(?) AND deleted_at IS NULL"
+            ))
+            .bind(format!("%{}%", escape_like(pattern)));
+
+            let circle_rows = circle_query
+                .fetch_all(&self.db)
+                .await
+                .map_err(|e| query_error(e, "Failed to fetch circles by name"))?;
+
+            let mut circles = Vec::new();
+            for circle_row in circle_rows {
+                circles.push(self.hydrate(circle_row).await?);
+            }
+
+            Ok(circles)
+        })
+        .await
+    }
+
+    async fn find_by_owner_major(&self, major: Major) -> Result<Vec<Circle>, anyhow::Error> {
+        self.run("find_by_owner_major", async {
+            tracing::info!("find_circles_by_owner_major : {:?}", major);
+            let circle_query = sqlx::query(select_circles!(
+                "INNER JOIN members AS owners ON owners.id = circles.owner_id \
+                 WHERE owners.major = ? AND circles.deleted_at IS NULL \
+                 AND owners.deleted_at IS NULL \
+                 ORDER BY circles.id"
+            ))
+            .bind(String::from(major));
+
+            let circle_rows = circle_query
+                .fetch_all(&self.db)
+                .await
+                .map_err(|e| query_error(e, "Failed to fetch circles by owner major"))?;
+
+            let mut circles = Vec::new();
+            for circle_row in circle_rows {
+                circles.push(self.hydrate(circle_row).await?);
+            }
+
+            Ok(circles)
+        })
+        .await
+    }
+
+    async fn find_archived(&self) -> Result<Vec<ArchivedCircle>, anyhow::Error> {
+        self.run("find_archived", async {
+            tracing::info!("find_archived_circles");
+            let circle_rows = sqlx::query(
+                "SELECT circles_archive.*, \
+                 CAST(UNIX_TIMESTAMP(created_at) AS SIGNED) AS created_at_unix, \
+                 CAST(UNIX_TIMESTAMP(deleted_at) AS SIGNED) AS deleted_at_unix \
+                 FROM circles_archive ORDER BY deleted_at DESC, id",
+            )
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch archived circles"))?;
+            let member_rows = sqlx::query("SELECT * FROM members_archive")
+                .fetch_all(&self.db)
+                .await
+                .map_err(|e| query_error(e, "Failed to fetch archived members"))?;
+
+            let mut members_by_circle: HashMap<String, Vec<MemberData>> = HashMap::new();
+            for member_row in &member_rows {
+                members_by_circle
+                    .entry(member_row.get::<String, _>("circle_id"))
+                    .or_default()
+                    .push(member_data(member_row));
+            }
+
+            circle_rows
+                .iter()
+                .map(|circle_row| {
+                    let members = members_by_circle
+                        .remove(&circle_row.get::<String, _>("id"))
+                        .unwrap_or_default();
+                    Ok(ArchivedCircle {
+                        circle: assemble(circle_row, members)?,
+                        deleted_at: Timestamp::from_unix_seconds(
+                            circle_row.get::<i64, _>("deleted_at_unix"),
+                        ),
+                    })
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn find_removed_members(
+        &self,
+        circle_id: &CircleId,
+    ) -> Result<Vec<RemovedMember>, anyhow::Error> {
+        self.run("find_removed_members", async {
+            tracing::info!("find_removed_members : {:?}", circle_id);
+            let member_rows = sqlx::query(
+                "SELECT *, CAST(UNIX_TIMESTAMP(deleted_at) AS SIGNED) AS deleted_at_unix \
+                 FROM members WHERE circle_id = ? AND deleted_at IS NOT NULL \
+                 ORDER BY deleted_at, id",
+            )
+            .bind(circle_id.to_string())
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch removed members"))?;
+
+            member_rows
+                .iter()
+                .map(|member_row| {
+                    Ok(RemovedMember {
+                        member: Member::try_from(member_data(member_row))?,
+                        deleted_at: Timestamp::from_unix_seconds(
+                            member_row.get::<i64, _>("deleted_at_unix"),
+                        ),
+                    })
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn find_membership_history(
+        &self,
+        member_id: &MemberId,
+    ) -> Result<Vec<MembershipEvent>, anyhow::Error> {
+        self.run("find_membership_history", async {
+            tracing::info!("find_membership_history : {:?}", member_id);
+            let history_rows = sqlx::query(
+                "SELECT circle_id, event, CAST(UNIX_TIMESTAMP(occurred_at) AS SIGNED) AS occurred_at_unix \
+                 FROM membership_history WHERE member_id = ? ORDER BY id",
+            )
+            .bind(member_id.to_string())
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| query_error(e, "Failed to fetch membership history"))?;
+
+            history_rows
+                .iter()
+                .map(|history_row| {
+                    Ok(MembershipEvent {
+                        circle_id: CircleId::from_str(&history_row.get::<String, _>("circle_id"))?,
+                        change: MembershipChange::try_from(
+                            history_row.get::<String, _>("event").as_str(),
+                        )?,
+                        at: Timestamp::from_unix_seconds(
+                            history_row.get::<i64, _>("occurred_at_unix"),
+                        ),
+                    })
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn count_circles(&self) -> Result<i64, anyhow::Error> {
+        self.run("count_circles", async {
+            tracing::info!("count_circles");
+            sqlx::query_scalar("SELECT COUNT(*) FROM circles WHERE deleted_at IS NULL")
+                .fetch_one(&self.db)
+                .await
+                .map_err(|e| query_error(e, "Failed to count circles"))
+        })
+        .await
+    }
+
+    async fn count_members(&self) -> Result<i64, anyhow::Error> {
+        self.run("count_members", async {
+            tracing::info!("count_members");
+            sqlx::query_scalar(
+                "SELECT COUNT(*) FROM members JOIN circles ON members.circle_id = circles.id \
+                 WHERE circles.deleted_at IS NULL AND members.deleted_at IS NULL",
+            )
+            .fetch_one(&self.db)
+            .await
+            .map_err(|e| query_error(e, "Failed to count members"))
+        })
+        .await
+    }
+
+    async fn with_transaction<F, R>(&self, f: F) -> Result<R, anyhow::Error>
+    where
+        F: FnOnce(&mut CircleTransaction) -> Result<R, anyhow::Error> + Send,
+        R: Send,
+    {
+        self.run("with_transaction", async {
+            let mut transaction = CircleTransaction::new();
+            let result = f(&mut transaction)?;
+            let rollback_only = transaction.is_rollback_only();
+            let writes = transaction
+                .into_writes()
+                .into_iter()
+                .map(|write| {
+                    let circle = match &write {
+                        CircleWrite::Create(circle)
+                        | CircleWrite::Update(circle)
+                        | CircleWrite::Delete(circle) => circle.clone(),
+                    };
+                    (write, CircleData::from(circle))
+                })
+                .collect::<Vec<_>>();
+            tracing::info!("circle_transaction : {} writes", writes.len());
+            if writes.is_empty() {
+                return Ok(result);
+            }
+
+            let failed_at = AtomicUsize::new(0);
+            self.retry
+                .run(|| self.write_transaction(&writes, rollback_only, &failed_at))
+                .await
+                .map_err(|e| {
+                    let circle_data = &writes[failed_at.load(Ordering::Relaxed)].1;
+                    match e {
+                        sqlx::Error::RowNotFound => {
+                            anyhow::Error::new(e).context(NotFoundError::new("Circle not found"))
+                        }
+                        e => circle_write_error(e, circle_data, "Failed to write circles"),
+                    }
+                })?;
+            Ok(result)
+        })
+        .await
+    }
+
+    async fn create(&self, circle: &Circle) -> Result<(), anyhow::Error> {
+        tracing::info!("create_circle : {:?}", circle);
+        self.with_transaction(|transaction| {
+            transaction.create(circle);
+            Ok(())
+        })
+        .await
+    }
+
+    async fn create_many(&self, circles: &[Circle]) -> Result<(), anyhow::Error> {
+        self.run("create_many", async {
+            tracing::info!("create_circles : {} circles", circles.len());
+            let mut tx = self
+                .db
+                .begin()
+                .await
+                .map_err(|e| query_error(e, "Failed to begin transaction"))?;
+
+            for circle in circles {
+                let circle_data = CircleData::from(circle.clone());
+                insert_circle_rows(&mut tx, &circle_data)
+                    .await
+                    .map_err(|e| circle_write_error(e, &circle_data, "Failed to insert circle"))?;
+            }
+
+            tx.commit()
+                .await
+                .map_err(|e| query_error(e, "Failed to commit transaction"))
+        })
+        .await
+    }
+
+    async fn update(&self, circle: &Circle) -> Result<Circle, anyhow::Error> {
+        self.run("update", async {
+            tracing::info!("update_circle : {:?}", circle);
+            let circle_data = CircleData::from(circle.clone());
+            self.retry
+                .run(|| self.write_update(std::slice::from_ref(&circle_data)))
+                .await
+                .map_err(|e| circle_write_error(e, &circle_data, "Failed to update circle"))?;
+
+            Ok(circle.clone())
+        })
+        .await
+    }
+
+    async fn update_many(&self, circles: &[Circle]) -> Result<(), anyhow::Error> {
+        self.run("update_many", async {
+            tracing::info!("update_circles : {} circles", circles.len());
+            let circles = circles
+                .iter()
+                .cloned()
+                .map(CircleData::from)
+                .collect::<Vec<_>>();
+            self.retry
+                .run(|| self.write_update(&circles))
+                .await
+                .map_err(|e| query_error(e, "Failed to update circles"))
+        })
+        .await
+    }
+
+    async fn set_members(
+        &self,
+        circle_id: &CircleId,
+        members: Vec<Member>,
+    ) -> Result<(), anyhow::Error> {
+        self.run("set_members", async {
+            tracing::info!("set_members : {:?} {} members", circle_id, members.len());
+            let circle_id = circle_id.to_string();
+            let replaced = self
+                .retry
+                .run(|| self.write_set_members(&circle_id, &members))
+                .await
+                .map_err(|e| query_error(e, "Failed to set members"))?;
+            match replaced {
+                ReplacedMembers::Replaced => Ok(()),
+                ReplacedMembers::Missing => Err(NotFoundError::new("Circle not found").into()),
+                ReplacedMembers::Refused(error) => Err(error),
+            }
+        })
+        .await
+    }
+
+    async fn add_members(
+        &self,
+        circle_id: &CircleId,
+        members: &[Member],
+    ) -> Result<(), anyhow::Error> {
+        self.run("add_members", async {
+            tracing::info!("add_members : {:?} {} members", circle_id, members.len());
+            let circle_id = circle_id.to_string();
+            let members: Vec<MemberData> = members.iter().cloned().map(MemberData::from).collect();
+            let added = self
+                .retry
+                .run(|| self.write_add_members(&circle_id, &members))
+                .await
+                .map_err(|e| query_error(e, "Failed to add members"))?;
+            match added {
+                AddedMembers::Added => Ok(()),
+                AddedMembers::Missing => Err(NotFoundError::new("Circle not found").into()),
+                AddedMembers::Refused { members: current }
+                    if current + members.len() > Circle::MAX_MEMBERS =>
+                {
+                    Err(DomainError::TooManyMembers {
+                        max: Circle::MAX_MEMBERS,
+                    }
+                    .into())
+                }
+                AddedMembers::Refused { .. } => Err(DomainError::CapacityExceeded.into()),
+            }
+        })
+        .await
+    }
+
+    async fn delete(&self, circle: &Circle) -> Result<(), anyhow::Error> {
+        tracing::info!("delete_circle : {:?}", circle);
+        self.with_transaction(|transaction| {
+            transaction.delete(circle);
+            Ok(())
+        })
+        .await
+    }
+
+    async fn restore(&self, circle_id: &CircleId) -> Result<(), anyhow::Error> {
+        self.run("restore", async {
+            tracing::info!("restore_circle : {:?}", circle_id);
+            let restored = self
+                .write_restore(&circle_id.to_string())
+                .await
+                .map_err(|e| query_error(e, "Failed to restore circle"))?;
+
+            if !restored {
+                return Err(NotFoundError::new("Circle not found").into());
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn adjust_capacity(
+        &self,
+        circle_id: &CircleId,
+        delta: i16,
+    ) -> Result<i16, anyhow::Error> {
+        self.run("adjust_capacity", async {
+            tracing::info!("adjust_capacity : {:?} by {}", circle_id, delta);
+            let circle_id = circle_id.to_string();
+            let adjusted = self
+                .retry
+                .run(|| self.write_capacity_delta(&circle_id, delta))
+                .await
+                .map_err(|e| query_error(e, "Failed to adjust capacity"))?;
+            let (capacity, members) = match adjusted {
+                CapacityDelta::Adjusted(capacity) => return Ok(capacity),
+                CapacityDelta::Missing => return Err(NotFoundError::new("Circle not found").into()),
+                CapacityDelta::Unchanged { capacity, members } => (capacity, members),
+            };
+            // Only a `delta` of 0 gets past both checks without a write.
+            let capacity =
+                Capacity::try_from(capacity.saturating_add(delta)).map_err(DomainError::from)?;
+            if (i16::from(capacity) as usize) < members {
+                return Err(DomainError::from(CapacityError::BelowMembership {
+                    capacity: capacity.into(),
+                    members,
+                })
+                .into());
+            }
+            Ok(capacity.into())
+        })
+        .await
+    }
+
+    async fn upsert_by_name(&self, circle: &Circle) -> Result<Upserted, anyhow::Error> {
+        self.run("upsert_by_name", async {
+            tracing::info!("upsert_circle_by_name : {:?}", circle);
+            let circle_data = CircleData::from(circle.clone());
+            let upserted = self
+                .write_upsert(&circle_data)
+                .await
+                .map_err(|e| circle_write_error(e, &circle_data, "Failed to upsert circle"))?;
+            match upserted {
+                UpsertRow::Inserted => Ok(Upserted::Created(circle.clone())),
+                UpsertRow::Updated(id) => Ok(Upserted::Updated(
+                    self.load(&CircleId::from_str(&id)?).await?,
+                )),
+                UpsertRow::Deleted => Err(ConflictError::new(format!(
+                    "Circle name '{}' is already taken",
+                    circle.name
+                ))
+                .into()),
+                UpsertRow::BelowMembership { members } => {
+                    Err(DomainError::from(CapacityError::BelowMembership {
+                        capacity: circle_data.capacity,
+                        members,
+                    })
+                    .into())
+                }
+            }
+        })
+        .await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use domain::{
-        aggregate::{
-            circle::Circle,
-            member::Member,
-            value_object::{grade::Grade, major::Major},
-        },
-        interface::circle_repository_interface::CircleRepositoryInterface,
-    };
+    use domain::interface::circle_repository_interface::{NotFoundError, UnavailableError};
 
-    use super::CircleRepository;
-
-    #[tokio::test]
-    async fn test() -> anyhow::Result<()> {
-        let mut circle1 = build_circle()?;
-        let repository = CircleRepository::new();
-        assert!(repository.find_by_id(&circle1.id).await.is_err());
-        repository.create(&circle1).await?;
-        assert_eq!(repository.find_by_id(&circle1.id).await?, circle1);
-        circle1.name = "circle_name2".to_string();
-        repository.update(&circle1).await?;
-        assert_eq!(repository.find_by_id(&circle1.id).await?, circle1);
-        repository.delete(&circle1).await?;
-        assert!(repository.find_by_id(&circle1.id).await.is_err());
-        Ok(())
-    }
-
-    fn build_circle() -> anyhow::Result<Circle> {
-        Circle::new(
-            "Music club".to_string(),
-            Member::new("member_name1".to_string(), 21, Grade::Third, Major::Art),
-            3,
-        )
+    use super::{escape_like, names_circle_name_key, query_error};
+
+    #[test]
+    fn test_names_circle_name_key() {
+        assert!(names_circle_name_key(
+            "Duplicate entry 'Music club' for key 'uq_circles_name'"
+        ));
+        assert!(names_circle_name_key(
+            "Duplicate entry 'Music club' for key 'circles.uq_circles_name'"
+        ));
+        assert!(!names_circle_name_key(
+            "Duplicate entry 'c1-m1' for key 'PRIMARY'"
+        ));
+        assert!(!names_circle_name_key(
+            "Duplicate entry 'for key uq_circles_name' for key 'members.PRIMARY'"
+        ));
+    }
+
+    #[test]
+    fn test_query_error_keeps_the_sqlx_error() {
+        let error = query_error(sqlx::Error::RowNotFound, "Failed to fetch circle by id");
+        assert!(error.is::<NotFoundError>());
+        assert_eq!(error.to_string(), "Failed to fetch circle by id");
+        assert!(matches!(
+            error.downcast_ref::<sqlx::Error>(),
+            Some(sqlx::Error::RowNotFound)
+        ));
+
+        let error = query_error(sqlx::Error::PoolTimedOut, "Failed to count circles");
+        assert!(error.is::<UnavailableError>());
+        assert!(matches!(
+            error.downcast_ref::<sqlx::Error>(),
+            Some(sqlx::Error::PoolTimedOut)
+        ));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("music"), "music");
+        assert_eq!(escape_like("100%"), "100\\%");
+        assert_eq!(escape_like("a_b"), "a\\_b");
+        assert_eq!(escape_like("c:\\"), "c:\\\\");
+    }
+}
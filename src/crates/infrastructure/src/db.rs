@@ -8,6 +8,12 @@ pub struct Db {
     db: Arc<RwLock<HashMap<String, String>>>,
 }
 
+impl Default for Db {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Db {
     pub fn new() -> Self {
         Self {
@@ -40,6 +46,16 @@ impl Db {
         db.keys().cloned().collect()
     }
 
+    pub fn snapshot(&self) -> HashMap<String, String> {
+        let db = self.db.read().expect("read data from db");
+        db.clone()
+    }
+
+    pub fn restore(&self, snapshot: HashMap<String, String>) {
+        let mut db = self.db.write().expect("write data to db");
+        *db = snapshot;
+    }
+
     pub fn remove<K>(&self, key: K) -> anyhow::Result<()>
     where
         K: AsRef<str>,
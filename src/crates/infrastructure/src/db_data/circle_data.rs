@@ -1,9 +1,14 @@
 use std::str::FromStr;
 
-use domain::aggregate::{
-    circle::Circle,
-    member::Member,
-    value_object::{circle_id::CircleId, member_id::MemberId},
+use domain::{
+    aggregate::{
+        circle::Circle,
+        member::Member,
+        value_object::{
+            capacity::Capacity, circle_id::CircleId, member_id::MemberId, timestamp::Timestamp,
+        },
+    },
+    interface::circle_repository_interface::DataIntegrityError,
 };
 
 use super::member_data::MemberData;
@@ -16,32 +21,51 @@ pub struct CircleData {
     pub owner: MemberData,
     pub capacity: i16,
     pub members: Vec<MemberData>,
+    // Unix seconds, UTC.
+    #[serde(default)]
+    pub created_at: Option<i64>,
 }
 
 impl std::convert::TryFrom<CircleData> for Circle {
     type Error = anyhow::Error;
 
+    // The members table stores the owner as a regular row, which is the shape
+    // `reconstruct_from_rows` takes. Exactly one row may match `owner_id`;
+    // anything else means the stored roster is corrupt.
     fn try_from(data: CircleData) -> Result<Self, Self::Error> {
         let circle_id = CircleId::from_str(data.id.as_str())?;
         let owner_id = MemberId::from_str(data.owner_id.as_str())?;
         let members = data
             .members
             .into_iter()
-            .map(|member_data| MemberData::try_into(member_data))
+            .map(MemberData::try_into)
             .collect::<Result<Vec<Member>, _>>()?;
 
-        let owner = members
+        let owners = members
             .iter()
-            .find(|member| member.id == owner_id)
-            .ok_or_else(|| anyhow::Error::msg("Owner not found"))?
-            .clone();
-
-        Ok(Circle {
-            id: circle_id,
-            name: data.name,
-            capacity: data.capacity,
-            owner,
-            members,
+            .filter(|member| member.id == owner_id)
+            .count();
+        if owners > 1 {
+            return Err(anyhow::anyhow!(
+                "Circle {} has {} members claiming owner id {}; expected exactly one",
+                data.id,
+                owners,
+                data.owner_id
+            ));
+        }
+        if owners == 0 {
+            let error = DataIntegrityError::missing_owner(data.id.as_str(), data.owner_id.as_str());
+            tracing::error!("{}", error);
+            return Err(error.into());
+        }
+
+        let capacity = Capacity::try_from(data.capacity)?;
+
+        let circle =
+            Circle::reconstruct_from_rows(circle_id, data.name, &owner_id, capacity, members)?;
+        Ok(match data.created_at {
+            Some(seconds) => circle.with_created_at(Timestamp::from_unix_seconds(seconds)),
+            None => circle,
         })
     }
 }
@@ -53,8 +77,81 @@ impl std::convert::From<Circle> for CircleData {
             name: circle.name,
             owner_id: circle.owner.clone().id.into(),
             owner: MemberData::from(circle.owner),
-            capacity: circle.capacity as i16,
+            capacity: circle.capacity.into(),
             members: circle.members.into_iter().map(MemberData::from).collect(),
+            created_at: circle.created_at.map(Timestamp::unix_seconds),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::aggregate::{
+        circle::Circle,
+        member::Member,
+        value_object::{grade::Grade, major::Major},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_owner_is_split_from_members() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let member = Member::new("member".to_string(), 19, Grade::First, Major::Art);
+        let mut circle = Circle::new("Music club".to_string(), owner, Capacity::new(10)?)?;
+        circle.add_member(member.clone())?;
+
+        let mut data = CircleData::from(circle.clone());
+        data.members.push(data.owner.clone());
+
+        assert_eq!(Circle::try_from(data)?, circle);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_more_than_one_owner() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let circle = Circle::new("Music club".to_string(), owner, Capacity::new(10)?)?;
+
+        let mut data = CircleData::from(circle);
+        data.members.push(data.owner.clone());
+        data.members.push(data.owner.clone());
+
+        let error = Circle::try_from(data).unwrap_err();
+        assert!(error.to_string().contains("2 members claiming owner id"));
+        assert!(error.to_string().contains("expected exactly one"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_owner_row_is_an_integrity_error() -> anyhow::Result<()> {
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, Major::Music);
+        let circle = Circle::new("Music club".to_string(), owner, Capacity::new(10)?)?;
+        let data = CircleData::from(circle.clone());
+
+        let error = Circle::try_from(data).unwrap_err();
+        let error = error
+            .downcast_ref::<DataIntegrityError>()
+            .expect("a missing owner row should be a DataIntegrityError");
+        assert_eq!(error.circle_id, circle.id.to_string());
+        assert_eq!(error.owner_id, circle.owner.id.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_major_round_trip() -> anyhow::Result<()> {
+        let major = Major::Other("Astrophysics".to_string());
+        let owner = Member::new("owner".to_string(), 21, Grade::Third, major.clone());
+        let circle = Circle::new("Star club".to_string(), owner, Capacity::new(10)?)?;
+
+        let mut data = CircleData::from(circle.clone());
+        data.members.push(data.owner.clone());
+        let json = serde_json::to_string(&data)?;
+        assert!(json.contains("\"major\":\"Astrophysics\""));
+
+        let restored = Circle::try_from(serde_json::from_str::<CircleData>(&json)?)?;
+        assert_eq!(restored.owner.major, major);
+        assert_eq!(restored, circle);
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use std::future::Future;
+
+use domain::interface::circle_repository_interface::DeadlineExceededError;
+use tokio::time::Instant;
+
+tokio::task_local! {
+    static DEADLINE: Instant;
+}
+
+// Runs `future` with `deadline` visible to every repository call it makes on
+// the same task, so queries give up when the request does.
+pub async fn scope<F: Future>(deadline: Instant, future: F) -> F::Output {
+    DEADLINE.scope(deadline, future).await
+}
+
+pub fn current() -> Option<Instant> {
+    DEADLINE.try_with(|deadline| *deadline).ok()
+}
+
+// Drops `operation` once the current deadline passes. A dropped sqlx query
+// closes its connection instead of handing it back to the pool mid-result,
+// and an open transaction is rolled back. Outside a `scope` it runs unbounded.
+pub async fn bounded<T, F>(operation: F) -> Result<T, anyhow::Error>
+where
+    F: Future<Output = Result<T, anyhow::Error>>,
+{
+    let Some(deadline) = current() else {
+        return operation.await;
+    };
+    match tokio::time::timeout_at(deadline, operation).await {
+        Ok(result) => result,
+        Err(_) => {
+            tracing::warn!("repository call abandoned at request deadline");
+            Err(DeadlineExceededError.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use super::*;
+
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bounded_drops_operation_at_deadline() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let flag = DropFlag(dropped.clone());
+        let operation = async move {
+            let _flag = flag;
+            std::future::pending::<Result<(), anyhow::Error>>().await
+        };
+
+        let result = scope(
+            Instant::now() + Duration::from_millis(20),
+            bounded(operation),
+        )
+        .await;
+        assert!(result.unwrap_err().is::<DeadlineExceededError>());
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_bounded_without_deadline_runs_to_completion() {
+        assert_eq!(current(), None);
+        assert_eq!(bounded(async { Ok(1) }).await.unwrap(), 1);
+    }
+}
@@ -1,4 +1,9 @@
+pub mod caching_circle_repository;
 pub mod circle_repository;
 pub mod circle_repository_with_my_sql;
 pub mod db;
 pub mod db_data;
+pub mod deadline;
+pub mod retry;
+pub mod seed;
+pub mod slow_query;
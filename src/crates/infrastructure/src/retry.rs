@@ -0,0 +1,149 @@
+use std::{future::Future, time::Duration};
+
+use sqlx::{mysql::MySqlDatabaseError, MySql, Transaction};
+
+// MySQL error numbers worth retrying: deadlock and lock wait timeout.
+const TRANSIENT_ERROR_NUMBERS: [u16; 2] = [1213, 1205];
+
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(50),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            base_delay,
+        }
+    }
+
+    pub async fn run<T, F, Fut>(&self, mut operation: F) -> Result<T, sqlx::Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, sqlx::Error>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Err(e) if attempt < self.max_attempts && is_transient(&e) => {
+                    let delay = self.base_delay * 2u32.pow(attempt - 1);
+                    tracing::warn!(
+                        "transient database error (attempt {}/{}), retrying in {:?}: {:?}",
+                        attempt,
+                        self.max_attempts,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+pub fn is_transient(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Io(e) => matches!(
+            e.kind(),
+            std::io::ErrorKind::ConnectionReset | std::io::ErrorKind::ConnectionAborted
+        ),
+        sqlx::Error::Database(e) => e
+            .try_downcast_ref::<MySqlDatabaseError>()
+            .is_some_and(|e| TRANSIENT_ERROR_NUMBERS.contains(&e.number())),
+        _ => false,
+    }
+}
+
+// Commits `tx` for a write run under `RetryPolicy::run`. A connection lost
+// before COMMIT leaves nothing applied, but one lost while it is in flight
+// may follow a commit the server already made, and running the write again
+// would then trip over its own rows. That error is kept out of the retry.
+pub async fn commit(tx: Transaction<'_, MySql>) -> Result<(), sqlx::Error> {
+    tx.commit().await.map_err(commit_error)
+}
+
+fn commit_error(error: sqlx::Error) -> sqlx::Error {
+    match error {
+        sqlx::Error::Io(e) => sqlx::Error::Protocol(format!(
+            "connection lost during commit, outcome unknown: {e}"
+        )),
+        error => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, sync::Mutex};
+
+    use super::*;
+
+    fn connection_reset() -> sqlx::Error {
+        sqlx::Error::Io(io::Error::from(io::ErrorKind::ConnectionReset))
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors() -> anyhow::Result<()> {
+        let policy = RetryPolicy::new(3, Duration::from_millis(1));
+        let calls = Mutex::new(0);
+        let result = policy
+            .run(|| async {
+                let mut calls = calls.lock().unwrap();
+                *calls += 1;
+                if *calls <= 2 {
+                    Err(connection_reset())
+                } else {
+                    Ok(*calls)
+                }
+            })
+            .await?;
+        assert_eq!(result, 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_other_errors() {
+        let policy = RetryPolicy::new(3, Duration::from_millis(1));
+        let calls = Mutex::new(0);
+        let result: Result<(), _> = policy
+            .run(|| async {
+                *calls.lock().unwrap() += 1;
+                Err(sqlx::Error::RowNotFound)
+            })
+            .await;
+        assert!(matches!(result, Err(sqlx::Error::RowNotFound)));
+        assert_eq!(*calls.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_reset_during_commit() {
+        let policy = RetryPolicy::new(3, Duration::from_millis(1));
+        let calls = Mutex::new(0);
+        let result: Result<(), _> = policy
+            .run(|| async {
+                *calls.lock().unwrap() += 1;
+                Err(commit_error(connection_reset()))
+            })
+            .await;
+        assert!(matches!(result, Err(sqlx::Error::Protocol(_))));
+        assert_eq!(*calls.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_commit_passes_other_errors_through() {
+        let error = commit_error(sqlx::Error::RowNotFound);
+        assert!(matches!(error, sqlx::Error::RowNotFound));
+    }
+}
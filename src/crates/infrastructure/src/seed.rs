@@ -0,0 +1,95 @@
+use anyhow::Error;
+use domain::{
+    aggregate::{
+        circle::Circle,
+        member::Member,
+        value_object::{capacity::Capacity, grade::Grade, major::Major},
+    },
+    interface::circle_repository_interface::CircleRepositoryInterface,
+};
+
+// Fixture data for tests. Everything except ids is fixed, so the same spec
+// always seeds the same circle.
+#[derive(Clone, Debug)]
+pub struct CircleSpec {
+    pub name: String,
+    pub capacity: i16,
+    pub owner_name: String,
+    pub owner_major: Major,
+    pub member_names: Vec<String>,
+}
+
+impl CircleSpec {
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_members(mut self, member_names: &[&str]) -> Self {
+        self.member_names = member_names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+}
+
+impl Default for CircleSpec {
+    fn default() -> Self {
+        Self {
+            name: "Music club".to_string(),
+            capacity: 10,
+            owner_name: "John Lennon".to_string(),
+            owner_major: Major::Music,
+            member_names: Vec::new(),
+        }
+    }
+}
+
+// Builds the circle described by `spec` and stores it through `repository`.
+pub async fn seed_circle<R>(repository: &R, spec: CircleSpec) -> Result<Circle, Error>
+where
+    R: CircleRepositoryInterface,
+{
+    let owner = Member::new(spec.owner_name, 21, Grade::Third, spec.owner_major);
+    let mut circle = Circle::new(spec.name, owner, Capacity::new(spec.capacity)?)?;
+    for name in spec.member_names {
+        circle.add_member(Member::new(name, 19, Grade::First, Major::Art))?;
+    }
+    repository.create(&circle).await?;
+    Ok(circle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{seed_circle, CircleSpec};
+    use crate::circle_repository::CircleRepository;
+    use domain::interface::circle_repository_interface::CircleRepositoryInterface;
+
+    #[tokio::test]
+    async fn test_seed_circle() -> anyhow::Result<()> {
+        let repository = CircleRepository::new();
+        let circle = seed_circle(
+            &repository,
+            CircleSpec::named("Chess club").with_members(&["member1", "member2"]),
+        )
+        .await?;
+
+        assert_eq!(circle.name, "Chess club");
+        assert_eq!(i16::from(circle.capacity), 10);
+        assert_eq!(circle.owner.name, "John Lennon");
+        let names = circle
+            .members
+            .iter()
+            .map(|member| member.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["member1", "member2"]);
+        assert_eq!(repository.find_by_id(&circle.id).await?, circle);
+
+        let invalid = CircleSpec {
+            capacity: 1,
+            ..CircleSpec::named("Tiny club")
+        };
+        assert!(seed_circle(&repository, invalid).await.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use std::{future::Future, time::Duration};
+
+use tokio::time::Instant;
+
+// Runs `future` and warns once it has taken longer than `threshold`, naming
+// `operation`. Without a threshold nothing is timed.
+pub async fn logged<F: Future>(
+    threshold: Option<Duration>,
+    operation: &'static str,
+    future: F,
+) -> F::Output {
+    let Some(threshold) = threshold else {
+        return future.await;
+    };
+    let started = Instant::now();
+    let output = future.await;
+    let elapsed = started.elapsed();
+    if elapsed > threshold {
+        tracing::warn!(
+            operation,
+            elapsed_ms = elapsed.as_millis() as u64,
+            "slow repository call: {} took {:?}",
+            operation,
+            elapsed
+        );
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use tracing_subscriber::fmt::MakeWriter;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Captured {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    // The subscriber is only the default for this thread, which the
+    // single-threaded test runtime keeps every poll on.
+    #[tokio::test]
+    async fn test_slow_operation_is_logged_at_warn() {
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(captured.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let slow = async {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            1
+        };
+        assert_eq!(
+            logged(Some(Duration::from_millis(10)), "find_by_id", slow).await,
+            1
+        );
+        logged(Some(Duration::from_secs(5)), "count_circles", async {}).await;
+        logged(
+            None,
+            "find_all",
+            tokio::time::sleep(Duration::from_millis(30)),
+        )
+        .await;
+
+        let logs = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let lines = logs.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1, "{}", logs);
+        assert!(lines[0].contains("WARN"));
+        assert!(lines[0].contains("find_by_id"));
+        assert!(lines[0].contains("elapsed_ms"));
+    }
+}
@@ -0,0 +1,75 @@
+use std::{fmt, sync::Arc};
+
+use domain::aggregate::circle::Circle;
+
+// What a mutating usecase is about to do to a circle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Update,
+    Delete,
+    TransferOwnership,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Update => write!(f, "update"),
+            Action::Delete => write!(f, "delete"),
+            Action::TransferOwnership => write!(f, "transfer ownership of"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    Deny,
+}
+
+// Who may do what is an application rule, not a domain one, so it is asked
+// here: after the circle is loaded and before anything is written. `actor` is
+// the id the caller acts as, `None` when it gave none.
+pub trait Authorizer: Send + Sync {
+    fn authorize(&self, actor: Option<&str>, action: Action, circle: &Circle) -> Decision;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AllowAll;
+
+impl Authorizer for AllowAll {
+    fn authorize(&self, _actor: Option<&str>, _action: Action, _circle: &Circle) -> Decision {
+        Decision::Allow
+    }
+}
+
+pub type SharedAuthorizer = Arc<dyn Authorizer>;
+
+pub fn allow_all() -> SharedAuthorizer {
+    Arc::new(AllowAll)
+}
+
+// Returned (wrapped in `anyhow::Error`) when the authorizer denies an action.
+#[derive(Debug)]
+pub struct ForbiddenError {
+    pub action: Action,
+}
+
+impl fmt::Display for ForbiddenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Not allowed to {} this circle", self.action)
+    }
+}
+
+impl std::error::Error for ForbiddenError {}
+
+pub(crate) fn ensure_allowed(
+    authorizer: &dyn Authorizer,
+    actor: Option<&str>,
+    action: Action,
+    circle: &Circle,
+) -> Result<(), ForbiddenError> {
+    match authorizer.authorize(actor, action, circle) {
+        Decision::Allow => Ok(()),
+        Decision::Deny => Err(ForbiddenError { action }),
+    }
+}
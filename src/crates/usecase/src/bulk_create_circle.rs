@@ -0,0 +1,84 @@
+use std::fmt;
+
+use anyhow::Error;
+use domain::{
+    aggregate::circle_policy::CirclePolicy,
+    interface::circle_repository_interface::CircleRepositoryInterface,
+};
+
+use crate::create_circle::{CreateCircleInput, CreateCircleOutput};
+
+#[derive(Debug)]
+pub struct BulkCreateCircleOutput {
+    pub circles: Vec<CreateCircleOutput>,
+}
+
+#[derive(Debug)]
+pub enum BulkCreateCircleError {
+    InvalidElement { index: usize, error: Error },
+    Repository(Error),
+}
+
+impl fmt::Display for BulkCreateCircleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BulkCreateCircleError::InvalidElement { index, error } => {
+                write!(f, "circles[{}]: {}", index, error)
+            }
+            BulkCreateCircleError::Repository(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for BulkCreateCircleError {}
+
+pub struct BulkCreateCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+    policy: CirclePolicy,
+}
+
+impl<T> BulkCreateCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        BulkCreateCircleUsecase {
+            circle_repository,
+            policy: CirclePolicy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: CirclePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    // Every element is validated before anything is written, and the batch
+    // is persisted in a single repository call so it commits or fails as one.
+    pub async fn execute(
+        &self,
+        create_circle_inputs: Vec<CreateCircleInput>,
+    ) -> Result<BulkCreateCircleOutput, BulkCreateCircleError> {
+        let circles = create_circle_inputs
+            .into_iter()
+            .enumerate()
+            .map(|(index, input)| {
+                input
+                    .into_circle(&self.policy)
+                    .map_err(|error| BulkCreateCircleError::InvalidElement { index, error })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.circle_repository
+            .create_many(&circles)
+            .await
+            .map_err(BulkCreateCircleError::Repository)?;
+
+        Ok(BulkCreateCircleOutput {
+            circles: circles.into_iter().map(CreateCircleOutput::from).collect(),
+        })
+    }
+}
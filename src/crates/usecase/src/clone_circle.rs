@@ -0,0 +1,60 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use domain::{
+    aggregate::value_object::circle_id::CircleId,
+    interface::circle_repository_interface::CircleRepositoryInterface,
+};
+
+use crate::create_circle::CreateCircleOutput;
+
+#[derive(Debug, Deserialize)]
+pub struct CloneCircleInput {
+    pub id: String,
+    pub circle_name: String,
+    pub copy_members: bool,
+}
+
+impl CloneCircleInput {
+    pub fn new(id: String, circle_name: String, copy_members: bool) -> Self {
+        CloneCircleInput {
+            id,
+            circle_name,
+            copy_members,
+        }
+    }
+}
+
+pub struct CloneCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> CloneCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        CloneCircleUsecase { circle_repository }
+    }
+
+    pub async fn execute(
+        &self,
+        clone_circle_input: CloneCircleInput,
+    ) -> Result<CreateCircleOutput> {
+        let circle_id = CircleId::from_str(clone_circle_input.id.as_str())?;
+        let source = self.circle_repository.find_by_id(&circle_id).await?;
+        let circle = source.duplicate(
+            clone_circle_input.circle_name,
+            clone_circle_input.copy_members,
+        )?;
+        self.circle_repository
+            .create(&circle)
+            .await
+            .map(|_| CreateCircleOutput::from(circle))
+    }
+}
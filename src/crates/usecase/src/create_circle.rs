@@ -4,12 +4,15 @@ use serde::Deserialize;
 use domain::{
     aggregate::{
         circle::Circle,
+        circle_policy::CirclePolicy,
         member::Member,
-        value_object::{grade::Grade, major::Major},
+        value_object::{capacity::Capacity, grade::Grade, major::Major, member_role::MemberRole},
     },
     interface::circle_repository_interface::CircleRepositoryInterface,
 };
 
+use crate::validation::{check_length, normalize_name, ValidationError};
+
 #[derive(Debug, Deserialize)]
 pub struct CreateCircleInput {
     pub circle_name: String,
@@ -18,6 +21,30 @@ pub struct CreateCircleInput {
     pub owner_age: i16,
     pub owner_grade: i16,
     pub owner_major: String,
+    // Joined after the owner, in order.
+    pub members: Vec<CreateMemberInput>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateMemberInput {
+    pub name: String,
+    pub age: i16,
+    pub grade: i16,
+    pub major: String,
+    // `member` when absent.
+    pub role: Option<String>,
+}
+
+impl CreateMemberInput {
+    pub fn new(name: String, age: i16, grade: i16, major: String, role: Option<String>) -> Self {
+        CreateMemberInput {
+            name: normalize_name(&name),
+            age,
+            grade,
+            major,
+            role,
+        }
+    }
 }
 
 impl CreateCircleInput {
@@ -30,13 +57,60 @@ impl CreateCircleInput {
         owner_major: String,
     ) -> Self {
         CreateCircleInput {
-            circle_name,
+            circle_name: normalize_name(&circle_name),
             capacity,
-            owner_name,
+            owner_name: normalize_name(&owner_name),
             owner_age,
             owner_grade,
             owner_major,
+            members: vec![],
+        }
+    }
+
+    pub fn with_members(mut self, members: Vec<CreateMemberInput>) -> Self {
+        self.members = members;
+        self
+    }
+
+    // Field-level failures come back as a `ValidationError` naming the field.
+    pub(crate) fn into_circle(self, policy: &CirclePolicy) -> Result<Circle> {
+        check_length("circle_name", &self.circle_name)?;
+        check_length("owner_name", &self.owner_name)?;
+        check_length("owner_major", &self.owner_major)?;
+        let capacity = Capacity::try_from(self.capacity)?;
+        let grade = Grade::try_from(self.owner_grade)
+            .map_err(|_| ValidationError::new("owner_grade", "must be between 1 and 4"))?;
+        let major = Major::from(self.owner_major.as_str());
+        let owner = Member::new(self.owner_name, self.owner_age, grade, major);
+        let mut circle = Circle::new_with_policy(self.circle_name, owner, capacity, policy)?;
+        for (index, member) in self.members.into_iter().enumerate() {
+            check_length(format!("members[{}].name", index), &member.name)?;
+            check_length(format!("members[{}].major", index), &member.major)?;
+            let grade = Grade::try_from(member.grade).map_err(|_| {
+                ValidationError::new(
+                    format!("members[{}].grade", index),
+                    "must be between 1 and 4",
+                )
+            })?;
+            let major = Major::from(member.major.as_str());
+            let role = member
+                .role
+                .as_deref()
+                .map(MemberRole::try_from)
+                .transpose()
+                .map_err(|error| ValidationError::new(format!("members[{}].role", index), error))?
+                .unwrap_or_default();
+            circle
+                .add_member_with_policy(
+                    Member {
+                        role,
+                        ..Member::new(member.name, member.age, grade, major)
+                    },
+                    policy,
+                )
+                .map_err(|error| ValidationError::new(format!("members[{}]", index), error))?;
         }
+        Ok(circle)
     }
 }
 
@@ -44,6 +118,22 @@ impl CreateCircleInput {
 pub struct CreateCircleOutput {
     pub circle_id: String,
     pub owner_id: String,
+    // Every member row created with the circle, owner first.
+    pub member_ids: Vec<String>,
+}
+
+impl std::convert::From<Circle> for CreateCircleOutput {
+    fn from(circle: Circle) -> Self {
+        let member_ids = std::iter::once(&circle.owner)
+            .chain(circle.members.iter())
+            .map(|member| member.id.to_string())
+            .collect();
+        CreateCircleOutput {
+            circle_id: String::from(circle.id),
+            owner_id: String::from(circle.owner.id),
+            member_ids,
+        }
+    }
 }
 
 pub struct CreateCircleUsecase<T>
@@ -51,6 +141,8 @@ where
     T: CircleRepositoryInterface,
 {
     circle_repository: T,
+    policy: CirclePolicy,
+    dry_run: bool,
 }
 
 impl<T> CreateCircleUsecase<T>
@@ -58,35 +150,40 @@ where
     T: CircleRepositoryInterface,
 {
     pub fn new(circle_repository: T) -> Self {
-        CreateCircleUsecase { circle_repository }
+        CreateCircleUsecase {
+            circle_repository,
+            policy: CirclePolicy::default(),
+            dry_run: false,
+        }
+    }
+
+    pub fn with_policy(mut self, policy: CirclePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    // A dry run performs the insert and rolls it back, so the output carries
+    // the ids the circle would have been given.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
     }
 
     pub async fn execute(
         &mut self,
         circle_circle_input: CreateCircleInput,
     ) -> Result<CreateCircleOutput> {
-        let grade = Grade::try_from(circle_circle_input.owner_grade)?;
-
-        let major = Major::from(circle_circle_input.owner_major.as_str());
-
-        let owner = Member::new(
-            circle_circle_input.owner_name,
-            circle_circle_input.owner_age,
-            grade,
-            major,
-        );
-        let owner_id = owner.clone().id;
-        let circle = Circle::new(
-            circle_circle_input.circle_name,
-            owner,
-            circle_circle_input.capacity,
-        )?;
+        let circle = circle_circle_input.into_circle(&self.policy)?;
+        let dry_run = self.dry_run;
         self.circle_repository
-            .create(&circle)
-            .await
-            .map(|_| CreateCircleOutput {
-                circle_id: String::from(circle.id),
-                owner_id: String::from(owner_id),
+            .with_transaction(|transaction| {
+                transaction.create(&circle);
+                if dry_run {
+                    transaction.set_rollback_only();
+                }
+                Ok(())
             })
+            .await
+            .map(|_| CreateCircleOutput::from(circle))
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,77 @@
+use std::str::FromStr;
+
+use anyhow::Error;
+use domain::{
+    aggregate::value_object::circle_id::CircleId,
+    interface::circle_repository_interface::CircleRepositoryInterface,
+};
+use serde::Deserialize;
+
+use crate::authorization::{allow_all, ensure_allowed, Action, SharedAuthorizer};
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteCircleInput {
+    pub id: String,
+    #[serde(default)]
+    pub actor: Option<String>,
+}
+
+impl DeleteCircleInput {
+    pub fn new(id: String) -> Self {
+        DeleteCircleInput { id, actor: None }
+    }
+
+    pub fn with_actor(mut self, actor: Option<String>) -> Self {
+        self.actor = actor;
+        self
+    }
+}
+
+pub struct DeleteCircleOutput {
+    pub circle_id: String,
+}
+
+pub struct DeleteCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+    authorizer: SharedAuthorizer,
+}
+
+impl<T> DeleteCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        DeleteCircleUsecase {
+            circle_repository,
+            authorizer: allow_all(),
+        }
+    }
+
+    pub fn with_authorizer(mut self, authorizer: SharedAuthorizer) -> Self {
+        self.authorizer = authorizer;
+        self
+    }
+
+    pub async fn execute(
+        &self,
+        delete_circle_input: DeleteCircleInput,
+    ) -> Result<DeleteCircleOutput, Error> {
+        let circle_id = CircleId::from_str(delete_circle_input.id.as_str())?;
+        let circle = self.circle_repository.find_by_id(&circle_id).await?;
+        ensure_allowed(
+            &*self.authorizer,
+            delete_circle_input.actor.as_deref(),
+            Action::Delete,
+            &circle,
+        )?;
+        self.circle_repository
+            .delete(&circle)
+            .await
+            .map(|_| DeleteCircleOutput {
+                circle_id: String::from(circle.id),
+            })
+    }
+}
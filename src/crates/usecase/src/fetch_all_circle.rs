@@ -0,0 +1,129 @@
+use std::str::FromStr;
+
+use anyhow::{Error, Ok, Result};
+use serde::Deserialize;
+
+use domain::{
+    aggregate::value_object::circle_id::CircleId,
+    interface::circle_repository_interface::CircleRepositoryInterface,
+};
+
+use crate::{fetch_circle::FetchCircleOutput, validation::ValidationError};
+
+pub const DEFAULT_LIMIT: i64 = 20;
+pub const MAX_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct FetchAllCircleInput {
+    pub limit: i64,
+    pub offset: i64,
+    pub cursor: Option<String>,
+}
+
+impl FetchAllCircleInput {
+    // Out-of-range values are clamped rather than rejected. A cursor takes
+    // the place of the offset.
+    pub fn new(limit: Option<i64>, offset: Option<i64>, cursor: Option<String>) -> Self {
+        FetchAllCircleInput {
+            limit: limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+            offset: if cursor.is_some() {
+                0
+            } else {
+                offset.unwrap_or(0).max(0)
+            },
+            cursor,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FetchAllCircleOutput {
+    pub circles: Vec<FetchCircleOutput>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    // Present while more circles follow this page.
+    pub next_cursor: Option<String>,
+}
+
+// Cursors are the hex of the last circle id on a page, so clients treat them
+// as opaque and the encoding can change without breaking them.
+pub fn encode_cursor(circle_id: &CircleId) -> String {
+    circle_id
+        .to_string()
+        .bytes()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub fn decode_cursor(cursor: &str) -> Result<CircleId, ValidationError> {
+    let invalid = || ValidationError::new("cursor", "is not a valid cursor");
+    if cursor.is_empty() || !cursor.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| {
+            cursor
+                .get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(invalid)?;
+    let circle_id = String::from_utf8(bytes).map_err(|_| invalid())?;
+    CircleId::from_str(&circle_id).map_err(|_| invalid())
+}
+
+pub struct FetchAllCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> FetchAllCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        FetchAllCircleUsecase { circle_repository }
+    }
+
+    pub async fn execute(
+        &self,
+        fetch_all_circle_input: FetchAllCircleInput,
+    ) -> Result<FetchAllCircleOutput, Error> {
+        let FetchAllCircleInput {
+            limit,
+            offset,
+            cursor,
+        } = fetch_all_circle_input;
+        let (circles, total, has_more) = match cursor {
+            // One row past the page tells whether another page follows.
+            Some(cursor) => {
+                let after = decode_cursor(&cursor)?;
+                let mut circles = self.circle_repository.find_after(&after, limit + 1).await?;
+                let has_more = circles.len() as i64 > limit;
+                circles.truncate(limit as usize);
+                let total = self.circle_repository.count_circles().await?;
+                (circles, total, has_more)
+            }
+            None => {
+                let (circles, total) = self.circle_repository.find_page(limit, offset).await?;
+                let has_more = offset + (circles.len() as i64) < total;
+                (circles, total, has_more)
+            }
+        };
+        let next_cursor = circles
+            .last()
+            .filter(|_| has_more)
+            .map(|circle| encode_cursor(&circle.id));
+        Ok(FetchAllCircleOutput {
+            circles: circles.into_iter().map(FetchCircleOutput::from).collect(),
+            total,
+            limit,
+            offset,
+            next_cursor,
+        })
+    }
+}
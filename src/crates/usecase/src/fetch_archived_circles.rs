@@ -0,0 +1,52 @@
+use anyhow::{Error, Result};
+
+use domain::interface::circle_repository_interface::{ArchivedCircle, CircleRepositoryInterface};
+
+use crate::fetch_circle::FetchCircleOutput;
+
+#[derive(Debug)]
+pub struct ArchivedCircleOutput {
+    pub circle: FetchCircleOutput,
+    // RFC 3339, always UTC.
+    pub deleted_at: String,
+}
+
+impl std::convert::From<ArchivedCircle> for ArchivedCircleOutput {
+    fn from(ArchivedCircle { circle, deleted_at }: ArchivedCircle) -> Self {
+        ArchivedCircleOutput {
+            circle: FetchCircleOutput::from(circle),
+            deleted_at: deleted_at.into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FetchArchivedCirclesOutput {
+    pub circles: Vec<ArchivedCircleOutput>,
+}
+
+pub struct FetchArchivedCirclesUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> FetchArchivedCirclesUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        FetchArchivedCirclesUsecase { circle_repository }
+    }
+
+    pub async fn execute(&self) -> Result<FetchArchivedCirclesOutput, Error> {
+        let circles = self.circle_repository.find_archived().await?;
+        Ok(FetchArchivedCirclesOutput {
+            circles: circles
+                .into_iter()
+                .map(ArchivedCircleOutput::from)
+                .collect(),
+        })
+    }
+}
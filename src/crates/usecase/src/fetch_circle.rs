@@ -1,40 +1,144 @@
-use anyhow::{Error, Ok, Result};
-use serde::Deserialize;
+use std::str::FromStr;
 
-use domain::interface::circle_repository_interface::CircleRepositoryInterface;
+use anyhow::{Error, Result};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use domain::{
+    aggregate::{
+        circle::Circle,
+        member::Member,
+        value_object::{circle_id::CircleId, grade::Grade, member_role::MemberRole},
+    },
+    interface::circle_repository_interface::{CircleRepositoryInterface, RemovedMember},
+};
 
 #[derive(Debug, Deserialize)]
-pub struct FetchAllCircleInput {
-    pub id: i16,
+pub struct FetchCircleInput {
+    pub id: String,
+    // Also list members dropped from the roster, after the current ones.
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+impl FetchCircleInput {
+    pub fn new(id: String) -> Self {
+        FetchCircleInput {
+            id,
+            include_deleted: false,
+        }
+    }
+
+    pub fn with_include_deleted(mut self, include_deleted: bool) -> Self {
+        self.include_deleted = include_deleted;
+        self
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FetchCircleOutput {
+    pub circle_id: String,
+    pub circle_name: String,
+    pub capacity: i16,
+    pub owner: MemberOutput,
+    pub members: Vec<MemberOutput>,
+    pub available_slots: i16,
+    // RFC 3339, always UTC.
+    #[serde(default)]
+    pub created_at: Option<String>,
 }
 
-impl FetchAllCircleInput {
-    pub fn new(id: i16) -> Self {
-        FetchAllCircleInput { id }
+impl std::convert::From<Circle> for FetchCircleOutput {
+    fn from(circle: Circle) -> Self {
+        FetchCircleOutput {
+            available_slots: circle.available_slots(),
+            circle_id: circle.id.into(),
+            circle_name: circle.name,
+            capacity: circle.capacity.into(),
+            owner: MemberOutput::from(circle.owner),
+            members: circle.members.into_iter().map(MemberOutput::from).collect(),
+            created_at: circle.created_at.map(String::from),
+        }
     }
 }
 
-// TODO: Define the output struct
-#[derive(Debug)]
-pub struct FetchAllCircleOutput {}
+// Wire names are `id`, `name`, `age`, `grade`, `major`, `role` and `is_owner`.
+// `member_id` is accepted as an input alias for `id` but never written.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct MemberOutput {
+    #[serde(rename = "id", alias = "member_id")]
+    pub id: String,
+    pub name: String,
+    pub age: i16,
+    #[schema(value_type = i16)]
+    pub grade: Grade,
+    pub major: String,
+    #[serde(default)]
+    #[schema(value_type = String)]
+    pub role: MemberRole,
+    // Kept alongside `role` for older clients; true only on the owner.
+    #[serde(default)]
+    pub is_owner: bool,
+    // RFC 3339; only on members dropped from the roster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<String>,
+}
+
+impl std::convert::From<Member> for MemberOutput {
+    fn from(member: Member) -> Self {
+        MemberOutput {
+            id: member.id.into(),
+            name: member.name,
+            age: member.age,
+            grade: member.grade,
+            major: member.major.into(),
+            is_owner: member.role == MemberRole::Owner,
+            role: member.role,
+            deleted_at: None,
+        }
+    }
+}
 
-pub struct FetchAllCircleUsecase<T>
+impl std::convert::From<RemovedMember> for MemberOutput {
+    fn from(removed: RemovedMember) -> Self {
+        MemberOutput {
+            deleted_at: Some(removed.deleted_at.into()),
+            ..MemberOutput::from(removed.member)
+        }
+    }
+}
+
+pub struct FetchCircleUsecase<T>
 where
     T: CircleRepositoryInterface,
 {
     circle_repository: T,
 }
 
-impl<T> FetchAllCircleUsecase<T>
+impl<T> FetchCircleUsecase<T>
 where
     T: CircleRepositoryInterface,
 {
     pub fn new(circle_repository: T) -> Self {
-        FetchAllCircleUsecase { circle_repository }
+        FetchCircleUsecase { circle_repository }
     }
 
-    pub async fn execute(&self) -> Result<FetchAllCircleOutput, Error> {
-        self.circle_repository.find_all().await?;
-        Ok(FetchAllCircleOutput {})
+    pub async fn execute(
+        &self,
+        fetch_circle_input: FetchCircleInput,
+    ) -> Result<FetchCircleOutput, Error> {
+        let circle_id = CircleId::from_str(fetch_circle_input.id.as_str())?;
+        let circle = self.circle_repository.find_by_id(&circle_id).await?;
+        let mut output = FetchCircleOutput::from(circle);
+        if fetch_circle_input.include_deleted {
+            let removed = self
+                .circle_repository
+                .find_removed_members(&circle_id)
+                .await?;
+            output
+                .members
+                .extend(removed.into_iter().map(MemberOutput::from));
+        }
+        Ok(output)
     }
-}
\ No newline at end of file
+}
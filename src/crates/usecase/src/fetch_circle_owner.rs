@@ -0,0 +1,49 @@
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
+use serde::Deserialize;
+
+use domain::{
+    aggregate::value_object::circle_id::CircleId,
+    interface::circle_repository_interface::CircleRepositoryInterface,
+};
+
+use crate::fetch_circle::MemberOutput;
+
+#[derive(Debug, Deserialize)]
+pub struct FetchCircleOwnerInput {
+    pub circle_id: String,
+}
+
+impl FetchCircleOwnerInput {
+    pub fn new(circle_id: String) -> Self {
+        FetchCircleOwnerInput { circle_id }
+    }
+}
+
+pub struct FetchCircleOwnerUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> FetchCircleOwnerUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        FetchCircleOwnerUsecase { circle_repository }
+    }
+
+    // `find_by_id` doesn't tell a missing circle from a failed query, so any
+    // lookup failure is reported as the circle not being found.
+    pub async fn execute(
+        &self,
+        fetch_circle_owner_input: FetchCircleOwnerInput,
+    ) -> Result<MemberOutput, Error> {
+        let circle_id = CircleId::from_str(fetch_circle_owner_input.circle_id.as_str())?;
+        let circle = self.circle_repository.find_by_id(&circle_id).await?;
+        Ok(MemberOutput::from(circle.owner))
+    }
+}
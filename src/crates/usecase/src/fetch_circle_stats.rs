@@ -0,0 +1,63 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use anyhow::{Error, Result};
+use serde::Deserialize;
+
+use domain::{
+    aggregate::{circle_stats::CircleStats, value_object::circle_id::CircleId},
+    interface::circle_repository_interface::CircleRepositoryInterface,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct FetchCircleStatsInput {
+    pub circle_id: String,
+}
+
+impl FetchCircleStatsInput {
+    pub fn new(circle_id: String) -> Self {
+        FetchCircleStatsInput { circle_id }
+    }
+}
+
+#[derive(Debug)]
+pub struct FetchCircleStatsOutput {
+    pub average_age: f64,
+    // Keyed by grade number.
+    pub grade_distribution: BTreeMap<i16, usize>,
+    pub major_distribution: BTreeMap<String, usize>,
+}
+
+impl std::convert::From<CircleStats> for FetchCircleStatsOutput {
+    fn from(stats: CircleStats) -> Self {
+        FetchCircleStatsOutput {
+            average_age: stats.average_age,
+            grade_distribution: stats.grade_distribution,
+            major_distribution: stats.major_distribution,
+        }
+    }
+}
+
+pub struct FetchCircleStatsUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> FetchCircleStatsUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        FetchCircleStatsUsecase { circle_repository }
+    }
+
+    pub async fn execute(
+        &self,
+        fetch_circle_stats_input: FetchCircleStatsInput,
+    ) -> Result<FetchCircleStatsOutput, Error> {
+        let circle_id = CircleId::from_str(fetch_circle_stats_input.circle_id.as_str())?;
+        let circle = self.circle_repository.find_by_id(&circle_id).await?;
+        Ok(FetchCircleStatsOutput::from(CircleStats::of(&circle)))
+    }
+}
@@ -0,0 +1,66 @@
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
+use serde::Deserialize;
+
+use domain::{
+    aggregate::value_object::circle_id::CircleId,
+    interface::circle_repository_interface::CircleRepositoryInterface,
+};
+
+use crate::{fetch_circle::FetchCircleOutput, validation::ValidationError};
+
+// Keeps the `IN (...)` list, and the response, bounded.
+pub const MAX_IDS: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct FetchCirclesByIdsInput {
+    pub ids: Vec<String>,
+}
+
+impl FetchCirclesByIdsInput {
+    pub fn new(ids: Vec<String>) -> Self {
+        FetchCirclesByIdsInput { ids }
+    }
+}
+
+#[derive(Debug)]
+pub struct FetchCirclesByIdsOutput {
+    pub circles: Vec<FetchCircleOutput>,
+}
+
+pub struct FetchCirclesByIdsUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> FetchCirclesByIdsUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        FetchCirclesByIdsUsecase { circle_repository }
+    }
+
+    pub async fn execute(
+        &self,
+        fetch_circles_by_ids_input: FetchCirclesByIdsInput,
+    ) -> Result<FetchCirclesByIdsOutput, Error> {
+        if fetch_circles_by_ids_input.ids.len() > MAX_IDS {
+            return Err(
+                ValidationError::new("ids", format!("must hold at most {} ids", MAX_IDS)).into(),
+            );
+        }
+        let ids = fetch_circles_by_ids_input
+            .ids
+            .iter()
+            .map(|id| CircleId::from_str(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        let circles = self.circle_repository.find_by_ids(&ids).await?;
+        Ok(FetchCirclesByIdsOutput {
+            circles: circles.into_iter().map(FetchCircleOutput::from).collect(),
+        })
+    }
+}
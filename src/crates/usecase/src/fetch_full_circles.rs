@@ -0,0 +1,33 @@
+use anyhow::{Error, Result};
+
+use domain::interface::circle_repository_interface::CircleRepositoryInterface;
+
+use crate::fetch_circle::FetchCircleOutput;
+
+#[derive(Debug)]
+pub struct FetchFullCirclesOutput {
+    pub circles: Vec<FetchCircleOutput>,
+}
+
+pub struct FetchFullCirclesUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> FetchFullCirclesUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        FetchFullCirclesUsecase { circle_repository }
+    }
+
+    pub async fn execute(&self) -> Result<FetchFullCirclesOutput, Error> {
+        let circles = self.circle_repository.find_full_circles().await?;
+        Ok(FetchFullCirclesOutput {
+            circles: circles.into_iter().map(FetchCircleOutput::from).collect(),
+        })
+    }
+}
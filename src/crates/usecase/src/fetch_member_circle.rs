@@ -0,0 +1,49 @@
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
+use serde::Deserialize;
+
+use domain::{
+    aggregate::value_object::member_id::MemberId,
+    interface::circle_repository_interface::CircleRepositoryInterface,
+};
+
+use crate::fetch_circle::FetchCircleOutput;
+
+#[derive(Debug, Deserialize)]
+pub struct FetchMemberCircleInput {
+    pub member_id: String,
+}
+
+impl FetchMemberCircleInput {
+    pub fn new(member_id: String) -> Self {
+        FetchMemberCircleInput { member_id }
+    }
+}
+
+pub struct FetchMemberCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> FetchMemberCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        FetchMemberCircleUsecase { circle_repository }
+    }
+
+    pub async fn execute(
+        &self,
+        fetch_member_circle_input: FetchMemberCircleInput,
+    ) -> Result<FetchCircleOutput, Error> {
+        let member_id = MemberId::from_str(fetch_member_circle_input.member_id.as_str())?;
+        self.circle_repository
+            .find_by_member_id(&member_id)
+            .await
+            .map(FetchCircleOutput::from)
+    }
+}
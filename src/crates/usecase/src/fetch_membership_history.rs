@@ -0,0 +1,80 @@
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use domain::{
+    aggregate::value_object::member_id::MemberId,
+    interface::circle_repository_interface::{CircleRepositoryInterface, MembershipEvent},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct FetchMembershipHistoryInput {
+    pub member_id: String,
+}
+
+impl FetchMembershipHistoryInput {
+    pub fn new(member_id: String) -> Self {
+        FetchMembershipHistoryInput { member_id }
+    }
+}
+
+#[derive(Debug)]
+pub struct FetchMembershipHistoryOutput {
+    pub member_id: String,
+    pub history: Vec<MembershipEventOutput>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct MembershipEventOutput {
+    pub circle_id: String,
+    // `joined` or `left`.
+    pub event: String,
+    // RFC 3339, always UTC.
+    pub at: String,
+}
+
+impl std::convert::From<MembershipEvent> for MembershipEventOutput {
+    fn from(event: MembershipEvent) -> Self {
+        MembershipEventOutput {
+            circle_id: event.circle_id.into(),
+            event: event.change.as_str().to_string(),
+            at: event.at.into(),
+        }
+    }
+}
+
+pub struct FetchMembershipHistoryUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> FetchMembershipHistoryUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        FetchMembershipHistoryUsecase { circle_repository }
+    }
+
+    pub async fn execute(
+        &self,
+        fetch_membership_history_input: FetchMembershipHistoryInput,
+    ) -> Result<FetchMembershipHistoryOutput, Error> {
+        let member_id = MemberId::from_str(fetch_membership_history_input.member_id.as_str())?;
+        let history = self
+            .circle_repository
+            .find_membership_history(&member_id)
+            .await?;
+        Ok(FetchMembershipHistoryOutput {
+            member_id: member_id.into(),
+            history: history
+                .into_iter()
+                .map(MembershipEventOutput::from)
+                .collect(),
+        })
+    }
+}
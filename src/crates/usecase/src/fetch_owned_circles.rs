@@ -0,0 +1,54 @@
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
+use serde::Deserialize;
+
+use domain::{
+    aggregate::value_object::member_id::MemberId,
+    interface::circle_repository_interface::CircleRepositoryInterface,
+};
+
+use crate::fetch_circle::FetchCircleOutput;
+
+#[derive(Debug, Deserialize)]
+pub struct FetchOwnedCirclesInput {
+    pub owner_id: String,
+}
+
+impl FetchOwnedCirclesInput {
+    pub fn new(owner_id: String) -> Self {
+        FetchOwnedCirclesInput { owner_id }
+    }
+}
+
+#[derive(Debug)]
+pub struct FetchOwnedCirclesOutput {
+    pub circles: Vec<FetchCircleOutput>,
+}
+
+pub struct FetchOwnedCirclesUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> FetchOwnedCirclesUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        FetchOwnedCirclesUsecase { circle_repository }
+    }
+
+    pub async fn execute(
+        &self,
+        fetch_owned_circles_input: FetchOwnedCirclesInput,
+    ) -> Result<FetchOwnedCirclesOutput, Error> {
+        let owner_id = MemberId::from_str(fetch_owned_circles_input.owner_id.as_str())?;
+        let circles = self.circle_repository.find_owned_by(&owner_id).await?;
+        Ok(FetchOwnedCirclesOutput {
+            circles: circles.into_iter().map(FetchCircleOutput::from).collect(),
+        })
+    }
+}
@@ -0,0 +1,31 @@
+use anyhow::{Error, Result};
+
+use domain::interface::circle_repository_interface::CircleRepositoryInterface;
+
+#[derive(Debug)]
+pub struct FetchStatsOutput {
+    pub circles: i64,
+    pub members: i64,
+}
+
+pub struct FetchStatsUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> FetchStatsUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        FetchStatsUsecase { circle_repository }
+    }
+
+    pub async fn execute(&self) -> Result<FetchStatsOutput, Error> {
+        let circles = self.circle_repository.count_circles().await?;
+        let members = self.circle_repository.count_members().await?;
+        Ok(FetchStatsOutput { circles, members })
+    }
+}
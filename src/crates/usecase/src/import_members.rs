@@ -0,0 +1,189 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::Error;
+use domain::{
+    aggregate::{
+        member::Member,
+        value_object::{circle_id::CircleId, grade::Grade, major::Major, member_role::MemberRole},
+    },
+    interface::circle_repository_interface::{CircleRepositoryInterface, NotFoundError},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::validation::{check_length, normalize_name};
+
+#[derive(Debug, Deserialize)]
+pub struct ImportMembersInput {
+    pub circle_id: String,
+    pub rows: Vec<ImportMemberRow>,
+    // Reject the whole import if any row fails instead of skipping it.
+    pub strict: bool,
+}
+
+impl ImportMembersInput {
+    pub fn new(circle_id: String, rows: Vec<ImportMemberRow>, strict: bool) -> Self {
+        ImportMembersInput {
+            circle_id,
+            rows,
+            strict,
+        }
+    }
+}
+
+// One record as read from the file; fields stay text so each row can fail on
+// its own. `line` is where the record starts, for error reports.
+#[derive(Debug, Deserialize)]
+pub struct ImportMemberRow {
+    pub line: usize,
+    pub name: String,
+    pub age: String,
+    pub grade: String,
+    pub major: String,
+    pub role: Option<String>,
+}
+
+impl ImportMemberRow {
+    fn into_member(self) -> Result<Member, Error> {
+        let name = normalize_name(&self.name);
+        if name.is_empty() {
+            return Err(Error::msg("name must not be blank"));
+        }
+        check_length("name", &name)?;
+        let age = self
+            .age
+            .trim()
+            .parse::<i16>()
+            .map_err(|_| Error::msg(format!("age '{}' is not a number", self.age)))?;
+        let grade = self
+            .grade
+            .trim()
+            .parse::<i16>()
+            .ok()
+            .and_then(|grade| Grade::try_from(grade).ok())
+            .ok_or_else(|| Error::msg(format!("grade '{}' must be between 1 and 4", self.grade)))?;
+        if self.major.trim().is_empty() {
+            return Err(Error::msg("major must not be blank"));
+        }
+        check_length("major", self.major.trim())?;
+        let role = match self.role.as_deref().map(str::trim) {
+            None | Some("") => MemberRole::Member,
+            Some(role) => MemberRole::try_from(role)?,
+        };
+        Ok(Member {
+            role,
+            ..Member::new(name, age, grade, Major::from(self.major.trim()))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+pub struct RejectedRow {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub struct ImportMembersOutput {
+    pub circle_id: String,
+    // Members added, in file order.
+    pub member_ids: Vec<String>,
+    pub rejected: Vec<RejectedRow>,
+}
+
+#[derive(Debug)]
+pub enum ImportMembersError {
+    CircleNotFound,
+    // Strict mode only: nothing was imported.
+    Rejected(Vec<RejectedRow>),
+    Repository(Error),
+}
+
+impl fmt::Display for ImportMembersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportMembersError::CircleNotFound => write!(f, "Circle not found"),
+            ImportMembersError::Rejected(rows) => write!(f, "{} rows were rejected", rows.len()),
+            ImportMembersError::Repository(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ImportMembersError {}
+
+pub struct ImportMembersUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> ImportMembersUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        ImportMembersUsecase { circle_repository }
+    }
+
+    // Rows are admitted in order, so once the circle is full every later row
+    // is rejected too.
+    pub async fn execute(
+        &self,
+        import_members_input: ImportMembersInput,
+    ) -> Result<ImportMembersOutput, ImportMembersError> {
+        let circle_id = CircleId::from_str(import_members_input.circle_id.as_str())
+            .map_err(|_| ImportMembersError::CircleNotFound)?;
+        let mut circle = self
+            .circle_repository
+            .find_by_id(&circle_id)
+            .await
+            .map_err(|error| {
+                if error.is::<NotFoundError>() {
+                    ImportMembersError::CircleNotFound
+                } else {
+                    ImportMembersError::Repository(error)
+                }
+            })?;
+
+        let mut added = Vec::new();
+        let mut rejected = Vec::new();
+        for row in import_members_input.rows {
+            let line = row.line;
+            match row.into_member().and_then(|member| {
+                circle
+                    .add_member(member.clone())
+                    .map(|_| member)
+                    .map_err(Error::from)
+            }) {
+                Ok(member) => added.push(member),
+                Err(error) => rejected.push(RejectedRow {
+                    line,
+                    message: error.to_string(),
+                }),
+            }
+        }
+        if import_members_input.strict && !rejected.is_empty() {
+            return Err(ImportMembersError::Rejected(rejected));
+        }
+
+        // The rows were checked against the roster as read; `add_members`
+        // checks the capacity again as it writes, in case another add got
+        // there first.
+        if !added.is_empty() {
+            self.circle_repository
+                .add_members(&circle_id, &added)
+                .await
+                .map_err(ImportMembersError::Repository)?;
+        }
+
+        Ok(ImportMembersOutput {
+            member_ids: added
+                .into_iter()
+                .map(|member| String::from(member.id))
+                .collect(),
+            circle_id: String::from(circle_id),
+            rejected,
+        })
+    }
+}
@@ -1,3 +1,28 @@
+pub mod authorization;
+pub mod bulk_create_circle;
+pub mod clone_circle;
 pub mod create_circle;
+pub mod delete_circle;
+pub mod fetch_all_circle;
+pub mod fetch_archived_circles;
 pub mod fetch_circle;
+pub mod fetch_circle_owner;
+pub mod fetch_circle_stats;
+pub mod fetch_circles_by_ids;
+pub mod fetch_full_circles;
+pub mod fetch_member_circle;
+pub mod fetch_membership_history;
+pub mod fetch_owned_circles;
+pub mod fetch_stats;
+pub mod import_members;
+pub mod merge_circles;
+pub mod move_member;
+pub mod restore_circle;
+pub mod search_circle;
+pub mod search_members;
+pub mod set_members;
+pub mod stream_circles;
 pub mod update_circle;
+pub mod update_member;
+pub mod upsert_circle;
+pub mod validation;
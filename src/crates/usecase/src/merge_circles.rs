@@ -0,0 +1,150 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::Error;
+use domain::{
+    aggregate::value_object::circle_id::CircleId,
+    error::DomainError,
+    interface::circle_repository_interface::{CircleRepositoryInterface, NotFoundError},
+};
+use serde::Deserialize;
+
+use crate::{
+    authorization::{allow_all, ensure_allowed, Action, ForbiddenError, SharedAuthorizer},
+    fetch_circle::{FetchCircleOutput, MemberOutput},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct MergeCirclesInput {
+    pub target_circle_id: String,
+    pub source_circle_id: String,
+    #[serde(default)]
+    pub actor: Option<String>,
+}
+
+impl MergeCirclesInput {
+    pub fn new(target_circle_id: String, source_circle_id: String) -> Self {
+        MergeCirclesInput {
+            target_circle_id,
+            source_circle_id,
+            actor: None,
+        }
+    }
+
+    pub fn with_actor(mut self, actor: Option<String>) -> Self {
+        self.actor = actor;
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct MergeCirclesOutput {
+    pub circle: FetchCircleOutput,
+    pub source_circle_id: String,
+    // Source members left out because the target already had them.
+    pub skipped: Vec<MemberOutput>,
+}
+
+#[derive(Debug)]
+pub enum MergeCirclesError {
+    CircleNotFound,
+    SameCircle,
+    Forbidden(ForbiddenError),
+    Rejected(DomainError),
+    Repository(Error),
+}
+
+impl fmt::Display for MergeCirclesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeCirclesError::CircleNotFound => write!(f, "Circle not found"),
+            MergeCirclesError::SameCircle => write!(f, "A circle can't be merged into itself"),
+            MergeCirclesError::Forbidden(error) => write!(f, "{}", error),
+            MergeCirclesError::Rejected(error) => write!(f, "{}", error),
+            MergeCirclesError::Repository(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for MergeCirclesError {}
+
+pub struct MergeCirclesUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+    authorizer: SharedAuthorizer,
+}
+
+impl<T> MergeCirclesUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        MergeCirclesUsecase {
+            circle_repository,
+            authorizer: allow_all(),
+        }
+    }
+
+    pub fn with_authorizer(mut self, authorizer: SharedAuthorizer) -> Self {
+        self.authorizer = authorizer;
+        self
+    }
+
+    // The source is deleted in the same transaction that fills the target, so
+    // its archived copy shows the roster as it stood before the merge.
+    pub async fn execute(
+        &self,
+        merge_circles_input: MergeCirclesInput,
+    ) -> Result<MergeCirclesOutput, MergeCirclesError> {
+        let target_circle_id = CircleId::from_str(merge_circles_input.target_circle_id.as_str())
+            .map_err(|_| MergeCirclesError::CircleNotFound)?;
+        let source_circle_id = CircleId::from_str(merge_circles_input.source_circle_id.as_str())
+            .map_err(|_| MergeCirclesError::CircleNotFound)?;
+        if target_circle_id == source_circle_id {
+            return Err(MergeCirclesError::SameCircle);
+        }
+
+        let mut target = self
+            .circle_repository
+            .find_by_id(&target_circle_id)
+            .await
+            .map_err(lookup_error)?;
+        let source = self
+            .circle_repository
+            .find_by_id(&source_circle_id)
+            .await
+            .map_err(lookup_error)?;
+
+        let actor = merge_circles_input.actor.as_deref();
+        ensure_allowed(&*self.authorizer, actor, Action::Update, &target)
+            .and_then(|_| ensure_allowed(&*self.authorizer, actor, Action::Delete, &source))
+            .map_err(MergeCirclesError::Forbidden)?;
+
+        let skipped = target
+            .merge_from(&source)
+            .map_err(MergeCirclesError::Rejected)?;
+        self.circle_repository
+            .with_transaction(|transaction| {
+                transaction.delete(&source);
+                transaction.update(&target);
+                Ok(())
+            })
+            .await
+            .map_err(MergeCirclesError::Repository)?;
+
+        Ok(MergeCirclesOutput {
+            circle: FetchCircleOutput::from(target),
+            source_circle_id: String::from(source_circle_id),
+            skipped: skipped.into_iter().map(MemberOutput::from).collect(),
+        })
+    }
+}
+
+fn lookup_error(error: Error) -> MergeCirclesError {
+    if error.is::<NotFoundError>() {
+        MergeCirclesError::CircleNotFound
+    } else {
+        MergeCirclesError::Repository(error)
+    }
+}
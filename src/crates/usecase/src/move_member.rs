@@ -0,0 +1,135 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::Error;
+use domain::{
+    aggregate::value_object::{circle_id::CircleId, member_id::MemberId},
+    error::DomainError,
+    interface::circle_repository_interface::{CircleRepositoryInterface, NotFoundError},
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct MoveMemberInput {
+    pub from_circle_id: String,
+    pub to_circle_id: String,
+    pub member_id: String,
+}
+
+impl MoveMemberInput {
+    pub fn new(from_circle_id: String, to_circle_id: String, member_id: String) -> Self {
+        MoveMemberInput {
+            from_circle_id,
+            to_circle_id,
+            member_id,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MoveMemberOutput {
+    pub from_circle_id: String,
+    pub to_circle_id: String,
+    pub member_id: String,
+}
+
+#[derive(Debug)]
+pub enum MoveMemberError {
+    CircleNotFound,
+    MemberNotFound,
+    SameCircle,
+    OwnerCannotMove,
+    Rejected(DomainError),
+    Repository(Error),
+}
+
+impl fmt::Display for MoveMemberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveMemberError::CircleNotFound => write!(f, "Circle not found"),
+            MoveMemberError::MemberNotFound => write!(f, "Member not found in circle"),
+            MoveMemberError::SameCircle => write!(f, "Member is already in that circle"),
+            MoveMemberError::OwnerCannotMove => write!(f, "Owner can't be moved"),
+            MoveMemberError::Rejected(error) => write!(f, "{}", error),
+            MoveMemberError::Repository(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for MoveMemberError {}
+
+pub struct MoveMemberUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> MoveMemberUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        MoveMemberUsecase { circle_repository }
+    }
+
+    // Both circles are written in one repository call, so the member is never
+    // left in neither or both.
+    pub async fn execute(
+        &self,
+        move_member_input: MoveMemberInput,
+    ) -> Result<MoveMemberOutput, MoveMemberError> {
+        let from_circle_id = CircleId::from_str(move_member_input.from_circle_id.as_str())
+            .map_err(|_| MoveMemberError::CircleNotFound)?;
+        let to_circle_id = CircleId::from_str(move_member_input.to_circle_id.as_str())
+            .map_err(|_| MoveMemberError::CircleNotFound)?;
+        let member_id = MemberId::from_str(move_member_input.member_id.as_str())
+            .map_err(|_| MoveMemberError::MemberNotFound)?;
+        if from_circle_id == to_circle_id {
+            return Err(MoveMemberError::SameCircle);
+        }
+
+        let mut from = self
+            .circle_repository
+            .find_by_id(&from_circle_id)
+            .await
+            .map_err(lookup_error)?;
+        let mut to = self
+            .circle_repository
+            .find_by_id(&to_circle_id)
+            .await
+            .map_err(lookup_error)?;
+
+        if from.owner.id == member_id {
+            return Err(MoveMemberError::OwnerCannotMove);
+        }
+        let member = from
+            .members
+            .iter()
+            .find(|member| member.id == member_id)
+            .cloned()
+            .ok_or(MoveMemberError::MemberNotFound)?;
+
+        from.remove_member(&member)
+            .map_err(MoveMemberError::Rejected)?;
+        to.add_member(member).map_err(MoveMemberError::Rejected)?;
+
+        self.circle_repository
+            .update_many(&[from, to])
+            .await
+            .map_err(MoveMemberError::Repository)?;
+
+        Ok(MoveMemberOutput {
+            from_circle_id: String::from(from_circle_id),
+            to_circle_id: String::from(to_circle_id),
+            member_id: String::from(member_id),
+        })
+    }
+}
+
+fn lookup_error(error: Error) -> MoveMemberError {
+    if error.is::<NotFoundError>() {
+        MoveMemberError::CircleNotFound
+    } else {
+        MoveMemberError::Repository(error)
+    }
+}
@@ -0,0 +1,52 @@
+use std::str::FromStr;
+
+use anyhow::Error;
+use domain::{
+    aggregate::value_object::circle_id::CircleId,
+    interface::circle_repository_interface::CircleRepositoryInterface,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct RestoreCircleInput {
+    pub id: String,
+}
+
+impl RestoreCircleInput {
+    pub fn new(id: String) -> Self {
+        RestoreCircleInput { id }
+    }
+}
+
+pub struct RestoreCircleOutput {
+    pub circle_id: String,
+}
+
+pub struct RestoreCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> RestoreCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        RestoreCircleUsecase { circle_repository }
+    }
+
+    pub async fn execute(
+        &self,
+        restore_circle_input: RestoreCircleInput,
+    ) -> Result<RestoreCircleOutput, Error> {
+        let circle_id = CircleId::from_str(restore_circle_input.id.as_str())?;
+        self.circle_repository
+            .restore(&circle_id)
+            .await
+            .map(|_| RestoreCircleOutput {
+                circle_id: String::from(circle_id),
+            })
+    }
+}
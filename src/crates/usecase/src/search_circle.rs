@@ -0,0 +1,104 @@
+use anyhow::{Error, Result};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use domain::{
+    aggregate::{circle::Circle, value_object::major::Major},
+    interface::circle_repository_interface::CircleRepositoryInterface,
+};
+
+// At least one of the two should be set; with neither, every circle matches.
+#[derive(Debug, Deserialize)]
+pub struct SearchCircleInput {
+    pub name: Option<String>,
+    pub owner_major: Option<String>,
+}
+
+impl SearchCircleInput {
+    pub fn new(name: Option<String>) -> Self {
+        SearchCircleInput {
+            name,
+            owner_major: None,
+        }
+    }
+
+    pub fn with_owner_major(mut self, owner_major: Option<String>) -> Self {
+        self.owner_major = owner_major;
+        self
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CircleSummaryOutput {
+    pub circle_id: String,
+    pub circle_name: String,
+    pub capacity: i16,
+    pub owner_name: String,
+}
+
+impl std::convert::From<Circle> for CircleSummaryOutput {
+    fn from(circle: Circle) -> Self {
+        CircleSummaryOutput {
+            circle_id: circle.id.into(),
+            circle_name: circle.name,
+            capacity: circle.capacity.into(),
+            owner_name: circle.owner.name,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SearchCircleOutput {
+    pub circles: Vec<CircleSummaryOutput>,
+}
+
+pub struct SearchCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> SearchCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        SearchCircleUsecase { circle_repository }
+    }
+
+    // Given both, the owner's major narrows the query and the name is matched
+    // on what comes back, the same way `find_by_name_like` matches it.
+    pub async fn execute(
+        &self,
+        search_circle_input: SearchCircleInput,
+    ) -> Result<SearchCircleOutput, Error> {
+        let SearchCircleInput { name, owner_major } = search_circle_input;
+        let circles = match owner_major {
+            Some(owner_major) => {
+                let circles = self
+                    .circle_repository
+                    .find_by_owner_major(Major::from(owner_major.as_str()))
+                    .await?;
+                match name {
+                    Some(name) => {
+                        let name = name.to_lowercase();
+                        circles
+                            .into_iter()
+                            .filter(|circle| circle.name.to_lowercase().contains(&name))
+                            .collect()
+                    }
+                    None => circles,
+                }
+            }
+            None => {
+                self.circle_repository
+                    .find_by_name_like(name.as_deref().unwrap_or_default())
+                    .await?
+            }
+        };
+        Ok(SearchCircleOutput {
+            circles: circles.into_iter().map(CircleSummaryOutput::from).collect(),
+        })
+    }
+}
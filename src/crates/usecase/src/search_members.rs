@@ -0,0 +1,88 @@
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
+use serde::Deserialize;
+
+use domain::{
+    aggregate::value_object::circle_id::CircleId,
+    interface::circle_repository_interface::CircleRepositoryInterface,
+};
+
+use crate::fetch_circle::MemberOutput;
+
+// Without `name` every member is returned.
+#[derive(Debug, Deserialize)]
+pub struct SearchMembersInput {
+    pub circle_id: String,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+impl SearchMembersInput {
+    pub fn new(circle_id: String, name: Option<String>) -> Self {
+        SearchMembersInput {
+            circle_id,
+            name,
+            include_deleted: false,
+        }
+    }
+
+    pub fn with_include_deleted(mut self, include_deleted: bool) -> Self {
+        self.include_deleted = include_deleted;
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct SearchMembersOutput {
+    // Owner first, then the other members in roster order, then any dropped
+    // members in the order they were dropped.
+    pub members: Vec<MemberOutput>,
+}
+
+pub struct SearchMembersUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> SearchMembersUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        SearchMembersUsecase { circle_repository }
+    }
+
+    // Rosters are small, so the match runs over the loaded aggregate rather
+    // than in a query.
+    pub async fn execute(
+        &self,
+        search_members_input: SearchMembersInput,
+    ) -> Result<SearchMembersOutput, Error> {
+        let circle_id = CircleId::from_str(search_members_input.circle_id.as_str())?;
+        let circle = self.circle_repository.find_by_id(&circle_id).await?;
+        let removed = if search_members_input.include_deleted {
+            self.circle_repository
+                .find_removed_members(&circle_id)
+                .await?
+        } else {
+            Vec::new()
+        };
+        let needle = search_members_input
+            .name
+            .map(|name| name.trim().to_lowercase());
+        let members = std::iter::once(circle.owner)
+            .chain(circle.members)
+            .map(MemberOutput::from)
+            .chain(removed.into_iter().map(MemberOutput::from))
+            .filter(|member| match &needle {
+                Some(needle) => member.name.to_lowercase().contains(needle.as_str()),
+                None => true,
+            })
+            .collect();
+        Ok(SearchMembersOutput { members })
+    }
+}
@@ -0,0 +1,178 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::Error;
+use domain::{
+    aggregate::{
+        member::Member,
+        value_object::{
+            circle_id::CircleId, grade::Grade, major::Major, member_id::MemberId,
+            member_role::MemberRole,
+        },
+    },
+    interface::circle_repository_interface::{CircleRepositoryInterface, NotFoundError},
+};
+use serde::Deserialize;
+
+use crate::validation::{check_length, normalize_name};
+
+#[derive(Debug, Deserialize)]
+pub struct SetMembersInput {
+    pub circle_id: String,
+    pub members: Vec<SetMemberInput>,
+}
+
+impl SetMembersInput {
+    pub fn new(circle_id: String, members: Vec<SetMemberInput>) -> Self {
+        SetMembersInput { circle_id, members }
+    }
+}
+
+// `id` names a current member to keep, with its row; without it a new
+// member is created. Without `role` a kept member keeps its role and a new
+// one joins as `member`.
+#[derive(Debug, Deserialize)]
+pub struct SetMemberInput {
+    pub id: Option<String>,
+    pub name: String,
+    pub age: i16,
+    pub grade: i16,
+    pub major: String,
+    pub role: Option<String>,
+}
+
+impl SetMemberInput {
+    pub fn new(
+        id: Option<String>,
+        name: String,
+        age: i16,
+        grade: i16,
+        major: String,
+        role: Option<String>,
+    ) -> Self {
+        SetMemberInput {
+            id,
+            name: normalize_name(&name),
+            age,
+            grade,
+            major,
+            role,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SetMembersOutput {
+    pub circle_id: String,
+    // The new roster in request order; the owner is not included.
+    pub member_ids: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum SetMembersError {
+    CircleNotFound,
+    MemberNotFound,
+    InvalidInput(Error),
+    Repository(Error),
+}
+
+impl fmt::Display for SetMembersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetMembersError::CircleNotFound => write!(f, "Circle not found"),
+            SetMembersError::MemberNotFound => write!(f, "Member not found in circle"),
+            SetMembersError::InvalidInput(error) => write!(f, "{}", error),
+            SetMembersError::Repository(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for SetMembersError {}
+
+pub struct SetMembersUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> SetMembersUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        SetMembersUsecase { circle_repository }
+    }
+
+    pub async fn execute(
+        &self,
+        set_members_input: SetMembersInput,
+    ) -> Result<SetMembersOutput, SetMembersError> {
+        let circle_id = CircleId::from_str(set_members_input.circle_id.as_str())
+            .map_err(|_| SetMembersError::CircleNotFound)?;
+        let mut circle = self
+            .circle_repository
+            .find_by_id(&circle_id)
+            .await
+            .map_err(|error| {
+                if error.is::<NotFoundError>() {
+                    SetMembersError::CircleNotFound
+                } else {
+                    SetMembersError::Repository(error)
+                }
+            })?;
+
+        let mut members = Vec::with_capacity(set_members_input.members.len());
+        for (index, member) in set_members_input.members.into_iter().enumerate() {
+            check_length(format!("members[{}].name", index), &member.name)
+                .map_err(|error| SetMembersError::InvalidInput(error.into()))?;
+            check_length(format!("members[{}].major", index), &member.major)
+                .map_err(|error| SetMembersError::InvalidInput(error.into()))?;
+            let grade = Grade::try_from(member.grade)
+                .map_err(|error| SetMembersError::InvalidInput(error.into()))?;
+            let major = Major::from(member.major.as_str());
+            let role = member
+                .role
+                .as_deref()
+                .map(MemberRole::try_from)
+                .transpose()
+                .map_err(|error| SetMembersError::InvalidInput(error.into()))?;
+            let member = match member.id {
+                // Only ids already on the roster are accepted, so a client
+                // can't pull in a member of another circle.
+                Some(id) => {
+                    let id = MemberId::from_str(id.as_str())
+                        .map_err(|_| SetMembersError::MemberNotFound)?;
+                    let current = circle
+                        .members
+                        .iter()
+                        .find(|m| m.id == id)
+                        .ok_or(SetMembersError::MemberNotFound)?;
+                    let role = role.unwrap_or(current.role);
+                    Member::reconstruct(id, member.name, member.age, grade, major, role)
+                }
+                None => Member {
+                    role: role.unwrap_or_default(),
+                    ..Member::new(member.name, member.age, grade, major)
+                },
+            };
+            members.push(member);
+        }
+
+        circle
+            .replace_members(members.clone())
+            .map_err(|error| SetMembersError::InvalidInput(error.into()))?;
+        self.circle_repository
+            .set_members(&circle_id, members)
+            .await
+            .map_err(SetMembersError::Repository)?;
+
+        Ok(SetMembersOutput {
+            circle_id: String::from(circle_id),
+            member_ids: circle
+                .members
+                .into_iter()
+                .map(|member| String::from(member.id))
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,32 @@
+use anyhow::Error;
+use futures_util::{Stream, StreamExt};
+
+use domain::interface::circle_repository_interface::CircleRepositoryInterface;
+
+use crate::fetch_circle::FetchCircleOutput;
+
+pub struct StreamCirclesUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+}
+
+impl<T> StreamCirclesUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        StreamCirclesUsecase { circle_repository }
+    }
+
+    // The repository's stream names the `&self` borrow in its type without
+    // holding it, so the capture is spelled out and the result stays `'static`.
+    pub fn execute(
+        &self,
+    ) -> impl Stream<Item = Result<FetchCircleOutput, Error>> + Send + 'static + use<'_, T> {
+        self.circle_repository
+            .stream_all()
+            .map(|circle| circle.map(FetchCircleOutput::from))
+    }
+}
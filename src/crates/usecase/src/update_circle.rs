@@ -2,35 +2,74 @@ use std::str::FromStr;
 
 use anyhow::Error;
 use domain::{
-    aggregate::value_object::circle_id::CircleId,
+    aggregate::{
+        circle::CircleChanges,
+        circle_policy::CirclePolicy,
+        value_object::{capacity::Capacity, circle_id::CircleId, member_id::MemberId},
+    },
     interface::circle_repository_interface::CircleRepositoryInterface,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    authorization::{allow_all, ensure_allowed, Action, SharedAuthorizer},
+    validation::{check_length, normalize_name},
+};
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateCircleInput {
     pub id: String,
     pub circle_name: Option<String>,
     pub capacity: Option<i16>,
+    pub owner_id: Option<String>,
+    #[serde(default)]
+    pub actor: Option<String>,
 }
 
 impl UpdateCircleInput {
-    pub fn new(id: String, circle_name: Option<String>, capacity: Option<i16>) -> Self {
+    pub fn new(
+        id: String,
+        circle_name: Option<String>,
+        capacity: Option<i16>,
+        owner_id: Option<String>,
+    ) -> Self {
         UpdateCircleInput {
             id,
-            circle_name,
+            circle_name: circle_name.as_deref().map(normalize_name),
             capacity,
+            owner_id,
+            actor: None,
         }
     }
+
+    pub fn with_actor(mut self, actor: Option<String>) -> Self {
+        self.actor = actor;
+        self
+    }
+}
+
+// A field of the circle that an update gave a new value, under its wire name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangedField {
+    CircleName,
+    Capacity,
+    OwnerId,
 }
 
 pub struct UpdateCircleOutPut {
     pub circle_id: String,
+    // In field order; a field sent with the value it already had is left out.
+    pub changed: Vec<ChangedField>,
 }
 
 impl UpdateCircleOutPut {
     pub fn new(circle_id: String) -> Self {
-        UpdateCircleOutPut { circle_id }
+        UpdateCircleOutPut {
+            circle_id,
+            changed: Vec::new(),
+        }
     }
 }
 
@@ -39,6 +78,9 @@ where
     T: CircleRepositoryInterface,
 {
     circle_repository: T,
+    policy: CirclePolicy,
+    authorizer: SharedAuthorizer,
+    dry_run: bool,
 }
 
 impl<T> UpdateCircleUsecase<T>
@@ -46,7 +88,28 @@ where
     T: CircleRepositoryInterface,
 {
     pub fn new(circle_repository: T) -> Self {
-        UpdateCircleUsecase { circle_repository }
+        UpdateCircleUsecase {
+            circle_repository,
+            policy: CirclePolicy::default(),
+            authorizer: allow_all(),
+            dry_run: false,
+        }
+    }
+
+    pub fn with_authorizer(mut self, authorizer: SharedAuthorizer) -> Self {
+        self.authorizer = authorizer;
+        self
+    }
+
+    pub fn with_policy(mut self, policy: CirclePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    // A dry run performs the update and rolls it back.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
     }
 
     pub async fn execute(
@@ -54,17 +117,56 @@ where
         update_circle_input: UpdateCircleInput,
     ) -> Result<UpdateCircleOutPut, Error> {
         let circle_id = CircleId::from_str(update_circle_input.id.as_str())?;
+        if let Some(name) = &update_circle_input.circle_name {
+            check_length("circle_name", name)?;
+        }
+        let changes = CircleChanges {
+            name: update_circle_input.circle_name,
+            capacity: update_circle_input
+                .capacity
+                .map(Capacity::try_from)
+                .transpose()?,
+            owner_id: update_circle_input
+                .owner_id
+                .as_deref()
+                .map(MemberId::from_str)
+                .transpose()?,
+        };
         let mut circle = self.circle_repository.find_by_id(&circle_id).await?;
-
-        circle.update(
-            update_circle_input.circle_name,
-            update_circle_input.capacity,
-        );
+        let actor = update_circle_input.actor.as_deref();
+        ensure_allowed(&*self.authorizer, actor, Action::Update, &circle)?;
+        if changes
+            .owner_id
+            .as_ref()
+            .is_some_and(|owner_id| *owner_id != circle.owner.id)
+        {
+            ensure_allowed(&*self.authorizer, actor, Action::TransferOwnership, &circle)?;
+        }
+        let before = circle.clone();
+        // The ownership change is applied to the same aggregate, so it is
+        // persisted together with the other fields or not at all.
+        circle.apply_update(changes, &self.policy)?;
+        let changed = [
+            (ChangedField::CircleName, before.name != circle.name),
+            (ChangedField::Capacity, before.capacity != circle.capacity),
+            (ChangedField::OwnerId, before.owner.id != circle.owner.id),
+        ]
+        .into_iter()
+        .filter_map(|(field, differs)| differs.then_some(field))
+        .collect();
+        let dry_run = self.dry_run;
         self.circle_repository
-            .update(&circle)
+            .with_transaction(|transaction| {
+                transaction.update(&circle);
+                if dry_run {
+                    transaction.set_rollback_only();
+                }
+                Ok(())
+            })
             .await
-            .map(|_cirlce| UpdateCircleOutPut {
+            .map(|_| UpdateCircleOutPut {
                 circle_id: String::from(circle.id),
+                changed,
             })
     }
-}
\ No newline at end of file
+}
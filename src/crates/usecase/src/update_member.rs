@@ -0,0 +1,180 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::Error;
+use domain::{
+    aggregate::{
+        circle_policy::CirclePolicy,
+        value_object::{
+            circle_id::CircleId, grade::Grade, major::Major, member_id::MemberId,
+            member_role::MemberRole,
+        },
+    },
+    error::DomainError,
+    interface::circle_repository_interface::{CircleRepositoryInterface, NotFoundError},
+};
+use serde::Deserialize;
+
+use crate::validation::{check_length, normalize_name};
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMemberInput {
+    pub circle_id: String,
+    pub member_id: String,
+    pub name: Option<String>,
+    pub age: Option<i16>,
+    pub grade: Option<i16>,
+    pub major: Option<String>,
+    pub role: Option<String>,
+}
+
+impl UpdateMemberInput {
+    pub fn new(
+        circle_id: String,
+        member_id: String,
+        name: Option<String>,
+        age: Option<i16>,
+        grade: Option<i16>,
+        major: Option<String>,
+        role: Option<String>,
+    ) -> Self {
+        UpdateMemberInput {
+            circle_id,
+            member_id,
+            name: name.as_deref().map(normalize_name),
+            age,
+            grade,
+            major,
+            role,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UpdateMemberOutput {
+    pub circle_id: String,
+    pub member_id: String,
+}
+
+#[derive(Debug)]
+pub enum UpdateMemberError {
+    CircleNotFound,
+    MemberNotFound,
+    InvalidInput(Error),
+    Repository(Error),
+}
+
+impl fmt::Display for UpdateMemberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateMemberError::CircleNotFound => write!(f, "Circle not found"),
+            UpdateMemberError::MemberNotFound => write!(f, "Member not found in circle"),
+            UpdateMemberError::InvalidInput(error) => write!(f, "{}", error),
+            UpdateMemberError::Repository(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for UpdateMemberError {}
+
+pub struct UpdateMemberUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+    policy: CirclePolicy,
+}
+
+impl<T> UpdateMemberUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        UpdateMemberUsecase {
+            circle_repository,
+            policy: CirclePolicy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: CirclePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub async fn execute(
+        &self,
+        update_member_input: UpdateMemberInput,
+    ) -> Result<UpdateMemberOutput, UpdateMemberError> {
+        let circle_id = CircleId::from_str(update_member_input.circle_id.as_str())
+            .map_err(|_| UpdateMemberError::CircleNotFound)?;
+        let member_id = MemberId::from_str(update_member_input.member_id.as_str())
+            .map_err(|_| UpdateMemberError::MemberNotFound)?;
+
+        if let Some(name) = &update_member_input.name {
+            check_length("name", name)
+                .map_err(|error| UpdateMemberError::InvalidInput(error.into()))?;
+        }
+        if let Some(major) = &update_member_input.major {
+            check_length("major", major)
+                .map_err(|error| UpdateMemberError::InvalidInput(error.into()))?;
+        }
+        let grade = update_member_input
+            .grade
+            .map(Grade::try_from)
+            .transpose()
+            .map_err(|error| UpdateMemberError::InvalidInput(error.into()))?;
+        let major = match update_member_input.major {
+            Some(major) if major.trim().is_empty() => {
+                return Err(UpdateMemberError::InvalidInput(Error::msg(
+                    "Major must not be blank",
+                )))
+            }
+            major => major.map(|major| Major::from(major.as_str())),
+        };
+        let role = update_member_input
+            .role
+            .as_deref()
+            .map(MemberRole::try_from)
+            .transpose()
+            .map_err(|error| UpdateMemberError::InvalidInput(error.into()))?;
+
+        let mut circle = self
+            .circle_repository
+            .find_by_id(&circle_id)
+            .await
+            .map_err(|error| {
+                if error.is::<NotFoundError>() {
+                    UpdateMemberError::CircleNotFound
+                } else {
+                    UpdateMemberError::Repository(error)
+                }
+            })?;
+        circle
+            .update_member(
+                &member_id,
+                update_member_input.name,
+                update_member_input.age,
+                grade,
+                major,
+                &self.policy,
+            )
+            .map_err(|error| match error {
+                DomainError::MemberNotFound => UpdateMemberError::MemberNotFound,
+                error => UpdateMemberError::InvalidInput(error.into()),
+            })?;
+        if let Some(role) = role {
+            circle
+                .assign_role(&member_id, role)
+                .map_err(|error| UpdateMemberError::InvalidInput(error.into()))?;
+        }
+
+        self.circle_repository
+            .update(&circle)
+            .await
+            .map_err(UpdateMemberError::Repository)?;
+
+        Ok(UpdateMemberOutput {
+            circle_id: String::from(circle_id),
+            member_id: String::from(member_id),
+        })
+    }
+}
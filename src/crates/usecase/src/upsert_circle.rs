@@ -0,0 +1,50 @@
+use anyhow::Result;
+
+use domain::{
+    aggregate::circle_policy::CirclePolicy,
+    interface::circle_repository_interface::{CircleRepositoryInterface, Upserted},
+};
+
+use crate::create_circle::{CreateCircleInput, CreateCircleOutput};
+
+// The whole input is validated as for a create even when only the capacity
+// ends up applied, so a request is accepted or refused the same way whether
+// or not the name exists yet.
+#[derive(Debug)]
+pub enum UpsertCircleOutput {
+    Created(CreateCircleOutput),
+    Updated(CreateCircleOutput),
+}
+
+pub struct UpsertCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    circle_repository: T,
+    policy: CirclePolicy,
+}
+
+impl<T> UpsertCircleUsecase<T>
+where
+    T: CircleRepositoryInterface,
+{
+    pub fn new(circle_repository: T) -> Self {
+        UpsertCircleUsecase {
+            circle_repository,
+            policy: CirclePolicy::default(),
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        upsert_circle_input: CreateCircleInput,
+    ) -> Result<UpsertCircleOutput> {
+        let circle = upsert_circle_input.into_circle(&self.policy)?;
+        Ok(
+            match self.circle_repository.upsert_by_name(&circle).await? {
+                Upserted::Created(circle) => UpsertCircleOutput::Created(circle.into()),
+                Upserted::Updated(circle) => UpsertCircleOutput::Updated(circle.into()),
+            },
+        )
+    }
+}
@@ -0,0 +1,48 @@
+use std::fmt;
+
+// Returned (wrapped in `anyhow::Error`) when one field of the input is
+// rejected. `path` names it the way a client wrote it, e.g.
+// `members[1].grade`.
+#[derive(Debug)]
+pub struct ValidationError {
+    pub path: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(path: impl Into<String>, message: impl fmt::Display) -> Self {
+        Self {
+            path: path.into(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+// Matches the `VARCHAR(255)` name and major columns. MySQL counts that limit
+// in characters, so a name in Japanese fits as many characters as one in
+// English although it takes three times the bytes.
+pub const MAX_NAME_CHARS: usize = 255;
+
+pub fn check_length(path: impl Into<String>, value: &str) -> Result<(), ValidationError> {
+    if value.chars().count() > MAX_NAME_CHARS {
+        return Err(ValidationError::new(
+            path,
+            format!("must be at most {} characters", MAX_NAME_CHARS),
+        ));
+    }
+    Ok(())
+}
+
+// Trims a name and collapses every inner run of whitespace to one space, so
+// `"  Music   Club  "` is stored, and checked for uniqueness, as `"Music Club"`.
+pub fn normalize_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
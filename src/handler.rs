@@ -1,31 +1,134 @@
-use crate::AppState;
+use crate::{
+    config::{admin::reset_allowed, circle::default_capacity},
+    AppState,
+};
 use axum::{
-    extract::{Json, Path, State},
-    http::StatusCode,
-    response::IntoResponse,
+    body::Body,
+    extract::{Extension, Json, Path, Query, State},
+    http::{
+        header::{ACCEPT, CONTENT_DISPOSITION, CONTENT_TYPE, ETAG, IF_NONE_MATCH, LOCATION, VARY},
+        HeaderMap, StatusCode,
+    },
+    response::{IntoResponse, Response},
 };
+use domain::interface::circle_repository_interface::NotFoundError;
+use futures_util::StreamExt;
 use serde::Deserialize;
 use sqlx::Row;
-use std::env;
+use std::{
+    collections::BTreeMap,
+    env,
+    hash::{DefaultHasher, Hash, Hasher},
+};
 use usecase::{
-    create_circle::{CreateCircleInput, CreateCircleOutput, CreateCircleUsecase},
-    fetch_all_circle::FetchAllCircleUsecase,
+    authorization::SharedAuthorizer,
+    bulk_create_circle::{BulkCreateCircleError, BulkCreateCircleOutput, BulkCreateCircleUsecase},
+    clone_circle::{CloneCircleInput, CloneCircleUsecase},
+    create_circle::{
+        CreateCircleInput, CreateCircleOutput, CreateCircleUsecase, CreateMemberInput,
+    },
+    delete_circle::{DeleteCircleInput, DeleteCircleOutput, DeleteCircleUsecase},
+    fetch_all_circle::{
+        FetchAllCircleInput, FetchAllCircleOutput, FetchAllCircleUsecase, MAX_LIMIT,
+    },
+    fetch_archived_circles::{FetchArchivedCirclesOutput, FetchArchivedCirclesUsecase},
     fetch_circle::{FetchCircleInput, FetchCircleOutput, FetchCircleUsecase, MemberOutput},
-    update_circle::{UpdateCircleInput, UpdateCircleOutPut, UpdateCircleUsecase},
+    fetch_circle_owner::{FetchCircleOwnerInput, FetchCircleOwnerUsecase},
+    fetch_circle_stats::{FetchCircleStatsInput, FetchCircleStatsOutput, FetchCircleStatsUsecase},
+    fetch_circles_by_ids::{
+        FetchCirclesByIdsInput, FetchCirclesByIdsOutput, FetchCirclesByIdsUsecase,
+    },
+    fetch_full_circles::{FetchFullCirclesOutput, FetchFullCirclesUsecase},
+    fetch_member_circle::{FetchMemberCircleInput, FetchMemberCircleUsecase},
+    fetch_membership_history::{
+        FetchMembershipHistoryInput, FetchMembershipHistoryOutput, FetchMembershipHistoryUsecase,
+        MembershipEventOutput,
+    },
+    fetch_owned_circles::{
+        FetchOwnedCirclesInput, FetchOwnedCirclesOutput, FetchOwnedCirclesUsecase,
+    },
+    fetch_stats::{FetchStatsOutput, FetchStatsUsecase},
+    import_members::{
+        ImportMemberRow, ImportMembersError, ImportMembersInput, ImportMembersOutput,
+        ImportMembersUsecase, RejectedRow,
+    },
+    merge_circles::{
+        MergeCirclesError, MergeCirclesInput, MergeCirclesOutput, MergeCirclesUsecase,
+    },
+    move_member::{MoveMemberError, MoveMemberInput, MoveMemberOutput, MoveMemberUsecase},
+    restore_circle::{RestoreCircleInput, RestoreCircleOutput, RestoreCircleUsecase},
+    search_circle::{
+        CircleSummaryOutput, SearchCircleInput, SearchCircleOutput, SearchCircleUsecase,
+    },
+    search_members::{SearchMembersInput, SearchMembersOutput, SearchMembersUsecase},
+    set_members::{
+        SetMemberInput, SetMembersError, SetMembersInput, SetMembersOutput, SetMembersUsecase,
+    },
+    stream_circles::StreamCirclesUsecase,
+    update_circle::{ChangedField, UpdateCircleInput, UpdateCircleOutPut, UpdateCircleUsecase},
+    update_member::{
+        UpdateMemberError, UpdateMemberInput, UpdateMemberOutput, UpdateMemberUsecase,
+    },
+    upsert_circle::{UpsertCircleOutput, UpsertCircleUsecase},
+    validation::ValidationError,
 };
 
-pub async fn handle_get_version() -> String {
-    env!("CARGO_PKG_VERSION").to_string()
+mod csv;
+mod error;
+mod extract;
+mod fields;
+mod lenient;
+mod merge_patch;
+mod negotiate;
+
+pub use error::ApiError;
+pub use extract::{Actor, ApiJson};
+pub use merge_patch::Patch;
+pub use negotiate::Format;
+
+#[derive(Debug, Deserialize)]
+pub struct VersionQuery {
+    format: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct VersionResponseBody {
+    pub version: String,
+}
+
+// `?format=text` keeps the old plain-text body for scripts that expect it.
+pub async fn handle_get_version(Query(query): Query<VersionQuery>) -> Response {
+    let version = env!("CARGO_PKG_VERSION").to_string();
+    match query.format.as_deref() {
+        Some("text") => version.into_response(),
+        _ => Json(VersionResponseBody { version }).into_response(),
+    }
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
 pub struct CreateCircleRequestBody {
     pub circle_name: String,
-    pub capacity: i16,
+    // Falls back to DEFAULT_CIRCLE_CAPACITY when absent.
+    #[serde(default, deserialize_with = "lenient::option_capacity")]
+    pub capacity: Option<i16>,
     pub owner_name: String,
     pub owner_age: i16,
+    #[serde(deserialize_with = "lenient::i16_or_string")]
     pub owner_grade: i16,
     pub owner_major: String,
+    #[serde(default)]
+    pub members: Vec<CreateMemberRequestBody>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct CreateMemberRequestBody {
+    pub name: String,
+    pub age: i16,
+    #[serde(deserialize_with = "lenient::i16_or_string")]
+    pub grade: i16,
+    pub major: String,
+    #[serde(default)]
+    pub role: Option<String>,
 }
 
 impl std::convert::From<CreateCircleRequestBody> for CreateCircleInput {
@@ -37,23 +140,39 @@ impl std::convert::From<CreateCircleRequestBody> for CreateCircleInput {
             owner_age,
             owner_grade,
             owner_major,
+            members,
         }: CreateCircleRequestBody,
     ) -> Self {
         CreateCircleInput::new(
             circle_name,
-            capacity,
+            capacity.unwrap_or_else(default_capacity),
             owner_name,
             owner_age,
             owner_grade,
             owner_major,
         )
+        .with_members(
+            members
+                .into_iter()
+                .map(|member| {
+                    CreateMemberInput::new(
+                        member.name,
+                        member.age,
+                        member.grade,
+                        member.major,
+                        member.role,
+                    )
+                })
+                .collect(),
+        )
     }
 }
 
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
 pub struct CreateCircleResponseBody {
     pub circle_id: String,
     pub owner_id: String,
+    pub member_ids: Vec<String>,
 }
 
 impl std::convert::From<CreateCircleOutput> for CreateCircleResponseBody {
@@ -61,27 +180,144 @@ impl std::convert::From<CreateCircleOutput> for CreateCircleResponseBody {
         CreateCircleOutput {
             circle_id,
             owner_id,
+            member_ids,
         }: CreateCircleOutput,
     ) -> Self {
         CreateCircleResponseBody {
             circle_id,
             owner_id,
+            member_ids,
         }
     }
 }
 
+// `?dry_run=true` runs validation and the write, then rolls it back.
+#[derive(Debug, Deserialize)]
+pub struct DryRunQuery {
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/circle",
+    params(("dry_run" = Option<bool>, Query, description = "Validate and roll back instead of committing")),
+    request_body = CreateCircleRequestBody,
+    responses(
+        (status = 201, description = "Circle created; `Location` points at it", body = CreateCircleResponseBody),
+        (status = 200, description = "The circle a dry run would create", body = CreateCircleResponseBody),
+        (status = 400, description = "Capacity out of range or an invalid field, named in `field`"),
+        (status = 409, description = "Circle name already taken")
+    )
+)]
 pub async fn handle_create_circle(
     State(state): State<AppState>,
-    Json(body): Json<CreateCircleRequestBody>,
-) -> Result<Json<CreateCircleResponseBody>, String> {
+    Query(query): Query<DryRunQuery>,
+    ApiJson(body): ApiJson<CreateCircleRequestBody>,
+) -> Result<Response, ApiError> {
     let circle_circle_input = CreateCircleInput::from(body);
-    let mut usecase = CreateCircleUsecase::new(state.circle_repository);
-    usecase
+    let mut usecase = CreateCircleUsecase::new(state.circle_repository).with_dry_run(query.dry_run);
+    let body = usecase
         .execute(circle_circle_input)
         .await
-        .map(CreateCircleResponseBody::from)
+        .map(CreateCircleResponseBody::from)?;
+    // Nothing was stored on a dry run, so there is nothing to point at.
+    if query.dry_run {
+        return Ok(Json(body).into_response());
+    }
+    let location = format!("/v1/circle/{}", body.circle_id);
+    Ok((StatusCode::CREATED, [(LOCATION, location)], Json(body)).into_response())
+}
+
+// Upserts by `circle_name`: an existing circle only takes the capacity, the
+// owner and member fields being used just when the circle is created.
+#[utoipa::path(
+    put,
+    path = "/v1/circle",
+    request_body = CreateCircleRequestBody,
+    responses(
+        (status = 200, description = "Capacity of the circle with that name updated", body = CreateCircleResponseBody),
+        (status = 201, description = "Circle created; `Location` points at it", body = CreateCircleResponseBody),
+        (status = 400, description = "Capacity out of range, below the current roster, or an invalid field"),
+        (status = 409, description = "Circle name held by a deleted circle")
+    )
+)]
+pub async fn handle_upsert_circle(
+    State(state): State<AppState>,
+    ApiJson(body): ApiJson<CreateCircleRequestBody>,
+) -> Result<Response, ApiError> {
+    let upsert_circle_input = CreateCircleInput::from(body);
+    let usecase = UpsertCircleUsecase::new(state.circle_repository);
+    Ok(match usecase.execute(upsert_circle_input).await? {
+        UpsertCircleOutput::Created(output) => {
+            let body = CreateCircleResponseBody::from(output);
+            let location = format!("/v1/circle/{}", body.circle_id);
+            (StatusCode::CREATED, [(LOCATION, location)], Json(body)).into_response()
+        }
+        UpsertCircleOutput::Updated(output) => {
+            Json(CreateCircleResponseBody::from(output)).into_response()
+        }
+    })
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct BulkCreateCircleResponseBody {
+    pub circles: Vec<CreateCircleResponseBody>,
+}
+
+impl std::convert::From<BulkCreateCircleOutput> for BulkCreateCircleResponseBody {
+    fn from(BulkCreateCircleOutput { circles }: BulkCreateCircleOutput) -> Self {
+        BulkCreateCircleResponseBody {
+            circles: circles
+                .into_iter()
+                .map(CreateCircleResponseBody::from)
+                .collect(),
+        }
+    }
+}
+
+impl std::convert::From<BulkCreateCircleError> for ApiError {
+    fn from(error: BulkCreateCircleError) -> Self {
+        match error {
+            BulkCreateCircleError::InvalidElement {
+                index,
+                error: ref inner,
+            } => {
+                let api_error = ApiError::bad_request(error.to_string()).with_field("index", index);
+                // Prefixed with the element, as serde reports paths into the array.
+                match inner.downcast_ref::<ValidationError>() {
+                    Some(inner) => {
+                        api_error.with_field("field", format!("[{}].{}", index, inner.path))
+                    }
+                    None => api_error,
+                }
+            }
+            BulkCreateCircleError::Repository(error) => ApiError::from(error),
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/circles",
+    request_body = Vec<CreateCircleRequestBody>,
+    responses(
+        (status = 200, description = "Circles created", body = BulkCreateCircleResponseBody),
+        (status = 400, description = "An element failed validation; nothing was created")
+    )
+)]
+pub async fn handle_bulk_create_circles(
+    State(state): State<AppState>,
+    ApiJson(body): ApiJson<Vec<CreateCircleRequestBody>>,
+) -> Result<Json<BulkCreateCircleResponseBody>, ApiError> {
+    let create_circle_inputs = body.into_iter().map(CreateCircleInput::from).collect();
+    let usecase = BulkCreateCircleUsecase::new(state.circle_repository);
+    usecase
+        .execute(create_circle_inputs)
+        .await
+        .map(BulkCreateCircleResponseBody::from)
         .map(Json)
-        .map_err(|e| e.to_string())
+        .map_err(ApiError::from)
 }
 
 #[derive(Debug, Deserialize)]
@@ -89,13 +325,43 @@ pub struct FetchCircleInputParam {
     id: String,
 }
 
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Deserialize)]
+pub struct FieldsQuery {
+    fields: Option<String>,
+    #[serde(default)]
+    include_deleted: bool,
+}
+
+// Keys are written in declaration order, but clients should treat the body as
+// an unordered object; appending or reordering fields is not a breaking change.
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
 pub struct FetcheCircleResponseBody {
     pub circle_id: String,
     pub circle_name: String,
     pub capacity: i16,
     pub owner: MemberOutput,
     pub members: Vec<MemberOutput>,
+    // Current members in `members`; the owner and dropped members are not
+    // included.
+    pub member_count: usize,
+    pub available_slots: i16,
+    // RFC 3339 in UTC, e.g. `2024-07-01T09:30:00Z`.
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+impl FetcheCircleResponseBody {
+    // Names accepted by `?fields=`; keep in step with the struct above.
+    pub const FIELDS: &'static [&'static str] = &[
+        "circle_id",
+        "circle_name",
+        "capacity",
+        "owner",
+        "members",
+        "member_count",
+        "available_slots",
+        "created_at",
+    ];
 }
 
 impl std::convert::From<FetchCircleOutput> for FetcheCircleResponseBody {
@@ -106,6 +372,8 @@ impl std::convert::From<FetchCircleOutput> for FetcheCircleResponseBody {
             capacity,
             owner,
             members,
+            available_slots,
+            created_at,
         }: FetchCircleOutput,
     ) -> Self {
         FetcheCircleResponseBody {
@@ -113,30 +381,653 @@ impl std::convert::From<FetchCircleOutput> for FetcheCircleResponseBody {
             circle_name,
             capacity,
             owner,
+            member_count: members
+                .iter()
+                .filter(|member| member.deleted_at.is_none())
+                .count(),
             members,
+            available_slots,
+            created_at,
         }
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/circle/{id}",
+    params(
+        ("id" = String, Path, description = "Circle id"),
+        ("fields" = Option<String>, Query, description = "Comma-separated top-level fields to return, e.g. `circle_name,capacity`"),
+        ("include_deleted" = Option<bool>, Query, description = "Also list members dropped from the roster, with `deleted_at` set")
+    ),
+    responses(
+        (status = 200, description = "Circle found; MessagePack with `Accept: application/msgpack`", body = FetcheCircleResponseBody),
+        (status = 304, description = "Circle unchanged since the ETag in If-None-Match"),
+        (status = 400, description = "`fields` names a field the circle does not have"),
+        (status = 404, description = "No circle with this id; `circle_not_found` with the id asked for")
+    )
+)]
 pub async fn handle_fetch_circle(
     State(state): State<AppState>,
     Path(param): Path<FetchCircleInputParam>,
-) -> Result<Json<FetcheCircleResponseBody>, String> {
-    let fetch_circle_input = FetchCircleInput::new(param.id);
+    Query(query): Query<FieldsQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let fields = query.fields.unwrap_or_default();
+    let names = fields::parse(&fields, FetcheCircleResponseBody::FIELDS).map_err(|field| {
+        ApiError::bad_request(format!("Unknown field '{}'", field)).with_field("field", field)
+    })?;
+
+    let circle_id = param.id.clone();
+    let fetch_circle_input =
+        FetchCircleInput::new(param.id).with_include_deleted(query.include_deleted);
     let usecase = FetchCircleUsecase::new(state.circle_repository);
+    let body = match usecase.execute(fetch_circle_input).await {
+        Ok(output) => FetcheCircleResponseBody::from(output),
+        Err(error) if error.is::<NotFoundError>() => {
+            return Err(ApiError::new(
+                StatusCode::NOT_FOUND,
+                "circle_not_found",
+                "Circle not found",
+            )
+            .with_field("circle_id", circle_id))
+        }
+        Err(error) => return Err(ApiError::from(error)),
+    };
+    let body = fields::project(
+        serde_json::to_value(body).map_err(|e| ApiError::internal(e.to_string()))?,
+        &names,
+    );
+
+    let format = Format::from_accept(&headers);
+    let serialized = format.encode(&body)?;
+    let etag = etag_for(&serialized);
+    if if_none_match(&headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, [(ETAG, etag)]).into_response());
+    }
+    Ok((
+        [
+            (ETAG, etag),
+            (CONTENT_TYPE, format.content_type().to_string()),
+            (VARY, ACCEPT.to_string()),
+        ],
+        serialized,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/circle/{id}/owner",
+    params(("id" = String, Path, description = "Circle id")),
+    responses(
+        (status = 200, description = "Owner of the circle", body = MemberOutput),
+        (status = 404, description = "Circle not found")
+    )
+)]
+pub async fn handle_fetch_circle_owner(
+    State(state): State<AppState>,
+    Path(param): Path<FetchCircleInputParam>,
+) -> Result<Json<MemberOutput>, ApiError> {
+    let fetch_circle_owner_input = FetchCircleOwnerInput::new(param.id);
+    let usecase = FetchCircleOwnerUsecase::new(state.circle_repository);
     usecase
-        .execute(fetch_circle_input)
+        .execute(fetch_circle_owner_input)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+// Owner included. `grade_distribution` lists every grade, `major_distribution`
+// only the majors someone takes.
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct FetchCircleStatsResponseBody {
+    pub average_age: f64,
+    pub grade_distribution: BTreeMap<i16, usize>,
+    pub major_distribution: BTreeMap<String, usize>,
+}
+
+impl std::convert::From<FetchCircleStatsOutput> for FetchCircleStatsResponseBody {
+    fn from(
+        FetchCircleStatsOutput {
+            average_age,
+            grade_distribution,
+            major_distribution,
+        }: FetchCircleStatsOutput,
+    ) -> Self {
+        FetchCircleStatsResponseBody {
+            average_age,
+            grade_distribution,
+            major_distribution,
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/circle/{id}/stats",
+    params(("id" = String, Path, description = "Circle id")),
+    responses(
+        (status = 200, description = "Age, grade and major figures for the roster", body = FetchCircleStatsResponseBody),
+        (status = 404, description = "Circle not found")
+    )
+)]
+pub async fn handle_fetch_circle_stats(
+    State(state): State<AppState>,
+    Path(param): Path<FetchCircleInputParam>,
+) -> Result<Json<FetchCircleStatsResponseBody>, ApiError> {
+    let fetch_circle_stats_input = FetchCircleStatsInput::new(param.id);
+    let usecase = FetchCircleStatsUsecase::new(state.circle_repository);
+    usecase
+        .execute(fetch_circle_stats_input)
+        .await
+        .map(FetchCircleStatsResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchMembersQuery {
+    name: Option<String>,
+    #[serde(default)]
+    include_deleted: bool,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct SearchMembersResponseBody {
+    pub members: Vec<MemberOutput>,
+}
+
+impl std::convert::From<SearchMembersOutput> for SearchMembersResponseBody {
+    fn from(SearchMembersOutput { members }: SearchMembersOutput) -> Self {
+        SearchMembersResponseBody { members }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/circle/{id}/members",
+    params(
+        ("id" = String, Path, description = "Circle id"),
+        ("name" = Option<String>, Query, description = "Case-insensitive substring of the member name"),
+        ("include_deleted" = Option<bool>, Query, description = "Also list members dropped from the roster, with `deleted_at` set")
+    ),
+    responses(
+        (status = 200, description = "Matching members, owner included", body = SearchMembersResponseBody),
+        (status = 404, description = "Circle not found")
+    )
+)]
+pub async fn handle_search_members(
+    State(state): State<AppState>,
+    Path(param): Path<FetchCircleInputParam>,
+    Query(query): Query<SearchMembersQuery>,
+) -> Result<Json<SearchMembersResponseBody>, ApiError> {
+    let search_members_input =
+        SearchMembersInput::new(param.id, query.name).with_include_deleted(query.include_deleted);
+    let usecase = SearchMembersUsecase::new(state.circle_repository);
+    usecase
+        .execute(search_members_input)
+        .await
+        .map(SearchMembersResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FetchMemberCircleInputParam {
+    member_id: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/members/{member_id}/circle",
+    params(("member_id" = String, Path, description = "Member id")),
+    responses(
+        (status = 200, description = "Circle the member belongs to", body = FetcheCircleResponseBody),
+        (status = 404, description = "Member is not in any circle")
+    )
+)]
+pub async fn handle_fetch_member_circle(
+    State(state): State<AppState>,
+    Path(param): Path<FetchMemberCircleInputParam>,
+) -> Result<Json<FetcheCircleResponseBody>, ApiError> {
+    let fetch_member_circle_input = FetchMemberCircleInput::new(param.member_id);
+    let usecase = FetchMemberCircleUsecase::new(state.circle_repository);
+    usecase
+        .execute(fetch_member_circle_input)
         .await
         .map(FetcheCircleResponseBody::from)
         .map(Json)
-        .map_err(|e| e.to_string())
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct MembershipHistoryResponseBody {
+    pub member_id: String,
+    // Oldest first.
+    pub history: Vec<MembershipEventOutput>,
+}
+
+impl std::convert::From<FetchMembershipHistoryOutput> for MembershipHistoryResponseBody {
+    fn from(
+        FetchMembershipHistoryOutput { member_id, history }: FetchMembershipHistoryOutput,
+    ) -> Self {
+        MembershipHistoryResponseBody { member_id, history }
+    }
 }
 
-pub async fn handle_fetch_all(State(state): State<AppState>) -> impl IntoResponse {
+#[utoipa::path(
+    get,
+    path = "/v1/members/{member_id}/history",
+    params(("member_id" = String, Path, description = "Member id")),
+    responses((status = 200, description = "Every circle the member joined or left; empty for an unknown member", body = MembershipHistoryResponseBody))
+)]
+pub async fn handle_fetch_membership_history(
+    State(state): State<AppState>,
+    Path(param): Path<FetchMemberCircleInputParam>,
+) -> Result<Json<MembershipHistoryResponseBody>, ApiError> {
+    let fetch_membership_history_input = FetchMembershipHistoryInput::new(param.member_id);
+    let usecase = FetchMembershipHistoryUsecase::new(state.circle_repository);
+    usecase
+        .execute(fetch_membership_history_input)
+        .await
+        .map(MembershipHistoryResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct OwnedCirclesResponseBody {
+    pub circles: Vec<FetcheCircleResponseBody>,
+}
+
+impl std::convert::From<FetchOwnedCirclesOutput> for OwnedCirclesResponseBody {
+    fn from(FetchOwnedCirclesOutput { circles }: FetchOwnedCirclesOutput) -> Self {
+        OwnedCirclesResponseBody {
+            circles: circles
+                .into_iter()
+                .map(FetcheCircleResponseBody::from)
+                .collect(),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/members/{member_id}/owned-circles",
+    params(("member_id" = String, Path, description = "Member id")),
+    responses((status = 200, description = "Circles owned by the member", body = OwnedCirclesResponseBody))
+)]
+pub async fn handle_fetch_owned_circles(
+    State(state): State<AppState>,
+    Path(param): Path<FetchMemberCircleInputParam>,
+) -> Result<Json<OwnedCirclesResponseBody>, ApiError> {
+    let fetch_owned_circles_input = FetchOwnedCirclesInput::new(param.member_id);
+    let usecase = FetchOwnedCirclesUsecase::new(state.circle_repository);
+    usecase
+        .execute(fetch_owned_circles_input)
+        .await
+        .map(OwnedCirclesResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct FullCirclesResponseBody {
+    pub circles: Vec<FetcheCircleResponseBody>,
+}
+
+impl std::convert::From<FetchFullCirclesOutput> for FullCirclesResponseBody {
+    fn from(FetchFullCirclesOutput { circles }: FetchFullCirclesOutput) -> Self {
+        FullCirclesResponseBody {
+            circles: circles
+                .into_iter()
+                .map(FetcheCircleResponseBody::from)
+                .collect(),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/circles/full",
+    responses((status = 200, description = "Circles with no free slots", body = FullCirclesResponseBody))
+)]
+pub async fn handle_fetch_full_circles(
+    State(state): State<AppState>,
+) -> Result<Json<FullCirclesResponseBody>, ApiError> {
+    let usecase = FetchFullCirclesUsecase::new(state.circle_repository);
+    usecase
+        .execute()
+        .await
+        .map(FullCirclesResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct ArchivedCircleResponseBody {
+    pub circle: FetcheCircleResponseBody,
+    // RFC 3339 in UTC.
+    pub deleted_at: String,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct ArchivedCirclesResponseBody {
+    pub circles: Vec<ArchivedCircleResponseBody>,
+}
+
+impl std::convert::From<FetchArchivedCirclesOutput> for ArchivedCirclesResponseBody {
+    fn from(FetchArchivedCirclesOutput { circles }: FetchArchivedCirclesOutput) -> Self {
+        ArchivedCirclesResponseBody {
+            circles: circles
+                .into_iter()
+                .map(|archived| ArchivedCircleResponseBody {
+                    circle: FetcheCircleResponseBody::from(archived.circle),
+                    deleted_at: archived.deleted_at,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/circles/archived",
+    responses((status = 200, description = "Deleted circles as they were when deleted, most recent first", body = ArchivedCirclesResponseBody))
+)]
+pub async fn handle_fetch_archived_circles(
+    State(state): State<AppState>,
+) -> Result<Json<ArchivedCirclesResponseBody>, ApiError> {
+    let usecase = FetchArchivedCirclesUsecase::new(state.circle_repository);
+    usecase
+        .execute()
+        .await
+        .map(ArchivedCirclesResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct BatchGetCirclesRequestBody {
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct BatchGetCirclesResponseBody {
+    pub circles: Vec<FetcheCircleResponseBody>,
+}
+
+impl std::convert::From<FetchCirclesByIdsOutput> for BatchGetCirclesResponseBody {
+    fn from(FetchCirclesByIdsOutput { circles }: FetchCirclesByIdsOutput) -> Self {
+        BatchGetCirclesResponseBody {
+            circles: circles
+                .into_iter()
+                .map(FetcheCircleResponseBody::from)
+                .collect(),
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/circles/batch-get",
+    request_body = BatchGetCirclesRequestBody,
+    responses(
+        (status = 200, description = "The circles that exist, in request order", body = BatchGetCirclesResponseBody),
+        (status = 400, description = "More ids than one request may ask for")
+    )
+)]
+pub async fn handle_batch_get_circles(
+    State(state): State<AppState>,
+    ApiJson(body): ApiJson<BatchGetCirclesRequestBody>,
+) -> Result<Json<BatchGetCirclesResponseBody>, ApiError> {
+    let fetch_circles_by_ids_input = FetchCirclesByIdsInput::new(body.ids);
+    let usecase = FetchCirclesByIdsUsecase::new(state.circle_repository);
+    usecase
+        .execute(fetch_circles_by_ids_input)
+        .await
+        .map(BatchGetCirclesResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportCsvQuery {
+    #[serde(default)]
+    members: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/circles.csv",
+    params(("members" = Option<bool>, Query, description = "One row per member instead of per circle")),
+    responses(
+        (status = 200, description = "Every circle as CSV", content_type = "text/csv")
+    )
+)]
+pub async fn handle_export_circles_csv(
+    State(state): State<AppState>,
+    Query(query): Query<ExportCsvQuery>,
+) -> Response {
+    let members = query.members;
+    // Pages are fetched as the client reads, so only one is held at a time.
+    // A failure after the first chunk can only cut the body short.
+    let pages = futures_util::stream::try_unfold(Some(0), move |offset| {
+        let usecase = FetchAllCircleUsecase::new(state.circle_repository.clone());
+        async move {
+            let Some(offset) = offset else {
+                return Ok(None);
+            };
+            let page = usecase
+                .execute(FetchAllCircleInput::new(
+                    Some(MAX_LIMIT),
+                    Some(offset),
+                    None,
+                ))
+                .await
+                .inspect_err(|e| tracing::error!("CSV export failed: {}", e))?;
+            let next = offset + page.circles.len() as i64;
+            let next = (!page.circles.is_empty() && next < page.total).then_some(next);
+            Ok::<_, anyhow::Error>(Some((csv::rows(&page.circles, members), next)))
+        }
+    });
+    let header = futures_util::stream::once(async move { Ok(csv::header(members)) });
+
+    (
+        [
+            (CONTENT_TYPE, csv::CONTENT_TYPE),
+            (CONTENT_DISPOSITION, "attachment; filename=\"circles.csv\""),
+        ],
+        Body::from_stream(futures_util::StreamExt::chain(header, pages)),
+    )
+        .into_response()
+}
+
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+#[utoipa::path(
+    get,
+    path = "/v1/circles/stream",
+    responses(
+        (status = 200, description = "Every circle as newline-delimited JSON, one `FetcheCircleResponseBody` per line", content_type = "application/x-ndjson")
+    )
+)]
+pub async fn handle_stream_circles(State(state): State<AppState>) -> Response {
+    // Each circle is written as soon as the repository yields it. As with the
+    // CSV export, a failure after the first line can only cut the body short.
+    let lines = StreamCirclesUsecase::new(state.circle_repository)
+        .execute()
+        .map(|circle| {
+            let circle = circle.inspect_err(|e| tracing::error!("NDJSON stream failed: {}", e))?;
+            let mut line = serde_json::to_vec(&FetcheCircleResponseBody::from(circle))?;
+            line.push(b'\n');
+            Ok::<_, anyhow::Error>(line)
+        });
+
+    (
+        [(CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+        Body::from_stream(lines),
+    )
+        .into_response()
+}
+
+fn etag_for(body: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == etag || tag == "*")
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FetchAllQuery {
+    limit: Option<i64>,
+    offset: Option<i64>,
+    cursor: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct FetchAllResponseBody {
+    pub data: Vec<FetcheCircleResponseBody>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    // Pass back as `cursor` for the next page; null on the last one.
+    pub next_cursor: Option<String>,
+}
+
+impl std::convert::From<FetchAllCircleOutput> for FetchAllResponseBody {
+    fn from(
+        FetchAllCircleOutput {
+            circles,
+            total,
+            limit,
+            offset,
+            next_cursor,
+        }: FetchAllCircleOutput,
+    ) -> Self {
+        FetchAllResponseBody {
+            data: circles
+                .into_iter()
+                .map(FetcheCircleResponseBody::from)
+                .collect(),
+            total,
+            limit,
+            offset,
+            next_cursor,
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/circle",
+    params(
+        ("limit" = Option<i64>, Query, description = "Page size, 1 to 100 (default 20)"),
+        ("offset" = Option<i64>, Query, description = "Circles to skip (default 0); ignored with `cursor`"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` from the previous page")
+    ),
+    responses((status = 200, description = "One page of circles; MessagePack with `Accept: application/msgpack`", body = FetchAllResponseBody))
+)]
+pub async fn handle_fetch_all(
+    State(state): State<AppState>,
+    Query(query): Query<FetchAllQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let fetch_all_circle_input = FetchAllCircleInput::new(query.limit, query.offset, query.cursor);
     let usecase = FetchAllCircleUsecase::new(state.circle_repository);
-    let circles = usecase.execute().await;
-    tracing::info!("circles: {:?}", circles);
-    (StatusCode::OK).into_response()
+    let body = usecase
+        .execute(fetch_all_circle_input)
+        .await
+        .map(FetchAllResponseBody::from)?;
+    Ok(Format::from_accept(&headers).respond(&body)?)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchCirclesQuery {
+    name: Option<String>,
+    owner_major: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct SearchCirclesResponseBody {
+    pub circles: Vec<CircleSummaryOutput>,
+}
+
+impl std::convert::From<SearchCircleOutput> for SearchCirclesResponseBody {
+    fn from(SearchCircleOutput { circles }: SearchCircleOutput) -> Self {
+        SearchCirclesResponseBody { circles }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/circles",
+    params(
+        ("name" = Option<String>, Query, description = "Case-insensitive substring of the circle name"),
+        ("owner_major" = Option<String>, Query, description = "Only circles whose owner studies this major, e.g. `Music`")
+    ),
+    responses(
+        (status = 200, description = "Matching circles", body = SearchCirclesResponseBody),
+        (status = 400, description = "Neither `name` nor `owner_major` was given")
+    )
+)]
+pub async fn handle_search_circles(
+    State(state): State<AppState>,
+    Query(query): Query<SearchCirclesQuery>,
+) -> Result<Json<SearchCirclesResponseBody>, ApiError> {
+    if query.name.is_none() && query.owner_major.is_none() {
+        return Err(ApiError::bad_request(
+            "Either `name` or `owner_major` is required",
+        ));
+    }
+    let search_circle_input =
+        SearchCircleInput::new(query.name).with_owner_major(query.owner_major);
+    let usecase = SearchCircleUsecase::new(state.circle_repository);
+    usecase
+        .execute(search_circle_input)
+        .await
+        .map(SearchCirclesResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct FetchStatsResponseBody {
+    pub circles: i64,
+    pub members: i64,
+}
+
+impl std::convert::From<FetchStatsOutput> for FetchStatsResponseBody {
+    fn from(FetchStatsOutput { circles, members }: FetchStatsOutput) -> Self {
+        FetchStatsResponseBody { circles, members }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/stats",
+    responses((status = 200, description = "Circle and member counts", body = FetchStatsResponseBody))
+)]
+pub async fn handle_fetch_stats(
+    State(state): State<AppState>,
+) -> Result<Json<FetchStatsResponseBody>, ApiError> {
+    let usecase = FetchStatsUsecase::new(state.circle_repository);
+    usecase
+        .execute()
+        .await
+        .map(FetchStatsResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
 }
 
 #[derive(Debug, Deserialize)]
@@ -144,43 +1035,772 @@ pub struct UpdateCircleInputParam {
     id: String,
 }
 
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
 pub struct UpdateCircleRequestBody {
     pub circle_name: Option<String>,
+    #[serde(default, deserialize_with = "lenient::option_capacity")]
     pub capacity: Option<i16>,
+    // Hands ownership to this member; the previous owner stays on as a member.
+    pub owner_id: Option<String>,
 }
 
 impl UpdateCircleRequestBody {
     pub fn convert_to_input(self, id: String) -> UpdateCircleInput {
-        UpdateCircleInput::new(id, self.circle_name, self.capacity)
+        UpdateCircleInput::new(id, self.circle_name, self.capacity, self.owner_id)
     }
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
 pub struct UpdateCircleResponseBody {
     pub circle_id: String,
+    // Fields the update gave a new value, e.g. `["capacity"]`.
+    pub changed: Vec<ChangedField>,
 }
 
 impl std::convert::From<UpdateCircleOutPut> for UpdateCircleResponseBody {
-    fn from(UpdateCircleOutPut { circle_id }: UpdateCircleOutPut) -> Self {
-        UpdateCircleResponseBody { circle_id }
+    fn from(UpdateCircleOutPut { circle_id, changed }: UpdateCircleOutPut) -> Self {
+        UpdateCircleResponseBody { circle_id, changed }
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/v1/circle/{id}",
+    params(
+        ("id" = String, Path, description = "Circle id"),
+        ("dry_run" = Option<bool>, Query, description = "Validate and roll back instead of committing"),
+        ("x-actor-id" = Option<String>, Header, description = "Id the caller acts as")
+    ),
+    request_body = UpdateCircleRequestBody,
+    responses(
+        (status = 200, description = "Circle updated", body = UpdateCircleResponseBody),
+        (status = 400, description = "Capacity out of range or below the current roster"),
+        (status = 403, description = "The actor may not update the circle or transfer its ownership"),
+        (status = 404, description = "New owner is not a member of the circle")
+    )
+)]
 pub async fn handle_update_circle(
     State(state): State<AppState>,
+    Extension(authorizer): Extension<SharedAuthorizer>,
+    Actor(actor): Actor,
     Path(path): Path<UpdateCircleInputParam>,
-    Json(body): Json<UpdateCircleRequestBody>,
-) -> Result<Json<UpdateCircleResponseBody>, String> {
-    let update_circle_input = body.convert_to_input(path.id.to_string());
-    let mut usecase = UpdateCircleUsecase::new(state.circle_repository);
+    Query(query): Query<DryRunQuery>,
+    ApiJson(body): ApiJson<UpdateCircleRequestBody>,
+) -> Result<Json<UpdateCircleResponseBody>, ApiError> {
+    let update_circle_input = body.convert_to_input(path.id.to_string()).with_actor(actor);
+    let mut usecase = UpdateCircleUsecase::new(state.circle_repository)
+        .with_authorizer(authorizer)
+        .with_dry_run(query.dry_run);
 
     usecase
         .execute(update_circle_input)
         .await
         .map(UpdateCircleResponseBody::from)
         .map(Json)
-        .map_err(|e| e.to_string())
+        .map_err(ApiError::from)
+}
+
+// JSON Merge Patch (RFC 7386) body for `PATCH /circle/:id`. Every member is
+// required on the circle, so `null` is rejected rather than clearing it.
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct PatchCircleRequestBody {
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub circle_name: Patch<String>,
+    #[serde(default, deserialize_with = "lenient::patch_capacity")]
+    #[schema(value_type = Option<i16>)]
+    pub capacity: Patch<i16>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub owner_id: Patch<String>,
+}
+
+impl PatchCircleRequestBody {
+    pub fn convert_to_input(self, id: String) -> Result<UpdateCircleInput, ApiError> {
+        let not_null = |field: &'static str| {
+            move |()| {
+                ApiError::bad_request(format!("{} cannot be null", field))
+                    .with_field("field", field)
+            }
+        };
+        Ok(UpdateCircleInput::new(
+            id,
+            self.circle_name
+                .required()
+                .map_err(not_null("circle_name"))?,
+            self.capacity.required().map_err(not_null("capacity"))?,
+            self.owner_id.required().map_err(not_null("owner_id"))?,
+        ))
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/v1/circle/{id}",
+    params(
+        ("id" = String, Path, description = "Circle id"),
+        ("x-actor-id" = Option<String>, Header, description = "Id the caller acts as")
+    ),
+    request_body(content = PatchCircleRequestBody, content_type = "application/merge-patch+json"),
+    responses(
+        (status = 200, description = "Circle updated", body = UpdateCircleResponseBody),
+        (status = 400, description = "Capacity out of range or below the roster, or a member set to null"),
+        (status = 403, description = "The actor may not update the circle or transfer its ownership"),
+        (status = 404, description = "New owner is not a member of the circle")
+    )
+)]
+pub async fn handle_patch_circle(
+    State(state): State<AppState>,
+    Extension(authorizer): Extension<SharedAuthorizer>,
+    Actor(actor): Actor,
+    Path(path): Path<UpdateCircleInputParam>,
+    ApiJson(body): ApiJson<PatchCircleRequestBody>,
+) -> Result<Json<UpdateCircleResponseBody>, ApiError> {
+    let update_circle_input = body
+        .convert_to_input(path.id.to_string())?
+        .with_actor(actor);
+    let mut usecase = UpdateCircleUsecase::new(state.circle_repository).with_authorizer(authorizer);
+
+    usecase
+        .execute(update_circle_input)
+        .await
+        .map(UpdateCircleResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMemberInputParam {
+    id: String,
+    member_id: String,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct UpdateMemberRequestBody {
+    pub name: Option<String>,
+    pub age: Option<i16>,
+    #[serde(default, deserialize_with = "lenient::option_i16_or_string")]
+    pub grade: Option<i16>,
+    pub major: Option<String>,
+    // `member` or `officer`; the owner changes through `owner_id` on the circle.
+    pub role: Option<String>,
+}
+
+impl UpdateMemberRequestBody {
+    pub fn convert_to_input(self, circle_id: String, member_id: String) -> UpdateMemberInput {
+        UpdateMemberInput::new(
+            circle_id, member_id, self.name, self.age, self.grade, self.major, self.role,
+        )
+    }
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct UpdateMemberResponseBody {
+    pub circle_id: String,
+    pub member_id: String,
+}
+
+impl std::convert::From<UpdateMemberOutput> for UpdateMemberResponseBody {
+    fn from(
+        UpdateMemberOutput {
+            circle_id,
+            member_id,
+        }: UpdateMemberOutput,
+    ) -> Self {
+        UpdateMemberResponseBody {
+            circle_id,
+            member_id,
+        }
+    }
+}
+
+impl std::convert::From<UpdateMemberError> for ApiError {
+    fn from(error: UpdateMemberError) -> Self {
+        match error {
+            UpdateMemberError::CircleNotFound => {
+                ApiError::new(StatusCode::NOT_FOUND, "circle_not_found", error.to_string())
+            }
+            UpdateMemberError::MemberNotFound => {
+                ApiError::new(StatusCode::NOT_FOUND, "member_not_found", error.to_string())
+            }
+            UpdateMemberError::InvalidInput(error) if error.is::<ValidationError>() => {
+                ApiError::from(error)
+            }
+            UpdateMemberError::InvalidInput(error) => ApiError::bad_request(error.to_string()),
+            UpdateMemberError::Repository(error) => ApiError::from(error),
+        }
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/v1/circle/{id}/members/{member_id}",
+    params(
+        ("id" = String, Path, description = "Circle id"),
+        ("member_id" = String, Path, description = "Member id")
+    ),
+    request_body = UpdateMemberRequestBody,
+    responses(
+        (status = 200, description = "Member updated", body = UpdateMemberResponseBody),
+        (status = 400, description = "Invalid grade or major"),
+        (status = 404, description = "Circle not found or member not in circle")
+    )
+)]
+pub async fn handle_update_member(
+    State(state): State<AppState>,
+    Path(path): Path<UpdateMemberInputParam>,
+    ApiJson(body): ApiJson<UpdateMemberRequestBody>,
+) -> Result<Json<UpdateMemberResponseBody>, ApiError> {
+    let update_member_input = body.convert_to_input(path.id, path.member_id);
+    let usecase = UpdateMemberUsecase::new(state.circle_repository);
+
+    usecase
+        .execute(update_member_input)
+        .await
+        .map(UpdateMemberResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct SetMemberRequestBody {
+    // Omit to add a new member; pass a current member's id to keep it.
+    pub id: Option<String>,
+    pub name: String,
+    pub age: i16,
+    #[serde(deserialize_with = "lenient::i16_or_string")]
+    pub grade: i16,
+    pub major: String,
+    // Omit to keep a current member's role; new members default to `member`.
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+impl std::convert::From<SetMemberRequestBody> for SetMemberInput {
+    fn from(
+        SetMemberRequestBody {
+            id,
+            name,
+            age,
+            grade,
+            major,
+            role,
+        }: SetMemberRequestBody,
+    ) -> Self {
+        SetMemberInput::new(id, name, age, grade, major, role)
+    }
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct SetMembersResponseBody {
+    pub circle_id: String,
+    pub member_ids: Vec<String>,
+}
+
+impl std::convert::From<SetMembersOutput> for SetMembersResponseBody {
+    fn from(
+        SetMembersOutput {
+            circle_id,
+            member_ids,
+        }: SetMembersOutput,
+    ) -> Self {
+        SetMembersResponseBody {
+            circle_id,
+            member_ids,
+        }
+    }
+}
+
+impl std::convert::From<SetMembersError> for ApiError {
+    fn from(error: SetMembersError) -> Self {
+        match error {
+            SetMembersError::CircleNotFound => {
+                ApiError::new(StatusCode::NOT_FOUND, "circle_not_found", error.to_string())
+            }
+            SetMembersError::MemberNotFound => {
+                ApiError::new(StatusCode::NOT_FOUND, "member_not_found", error.to_string())
+            }
+            SetMembersError::InvalidInput(error) => ApiError::from(error),
+            SetMembersError::Repository(error) => ApiError::from(error),
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/circle/{id}/members",
+    params(("id" = String, Path, description = "Circle id")),
+    request_body = Vec<SetMemberRequestBody>,
+    responses(
+        (status = 200, description = "Roster replaced; the owner is unchanged", body = SetMembersResponseBody),
+        (status = 400, description = "A member was rejected or the roster exceeds capacity"),
+        (status = 404, description = "Circle not found or an id is not a current member")
+    )
+)]
+pub async fn handle_set_members(
+    State(state): State<AppState>,
+    Path(path): Path<FetchCircleInputParam>,
+    ApiJson(body): ApiJson<Vec<SetMemberRequestBody>>,
+) -> Result<Json<SetMembersResponseBody>, ApiError> {
+    let set_members_input = SetMembersInput::new(
+        path.id,
+        body.into_iter().map(SetMemberInput::from).collect(),
+    );
+    let usecase = SetMembersUsecase::new(state.circle_repository);
+    usecase
+        .execute(set_members_input)
+        .await
+        .map(SetMembersResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+// `strict` rejects the whole file if any row fails; `lenient` imports the
+// rows that pass and reports the rest.
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    #[default]
+    Strict,
+    Lenient,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportMembersQuery {
+    #[serde(default)]
+    mode: ImportMode,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct ImportMembersResponseBody {
+    pub circle_id: String,
+    pub member_ids: Vec<String>,
+    pub rejected: Vec<RejectedRow>,
+}
+
+impl std::convert::From<ImportMembersOutput> for ImportMembersResponseBody {
+    fn from(
+        ImportMembersOutput {
+            circle_id,
+            member_ids,
+            rejected,
+        }: ImportMembersOutput,
+    ) -> Self {
+        ImportMembersResponseBody {
+            circle_id,
+            member_ids,
+            rejected,
+        }
+    }
+}
+
+impl std::convert::From<ImportMembersError> for ApiError {
+    fn from(error: ImportMembersError) -> Self {
+        match error {
+            ImportMembersError::CircleNotFound => {
+                ApiError::new(StatusCode::NOT_FOUND, "circle_not_found", error.to_string())
+            }
+            ImportMembersError::Rejected(ref rows) => {
+                let rows = serde_json::to_value(rows).unwrap_or_default();
+                ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    "import_rejected",
+                    error.to_string(),
+                )
+                .with_field("rejected", rows)
+            }
+            ImportMembersError::Repository(error) => ApiError::from(error),
+        }
+    }
+}
+
+// Columns are matched by the header row, so their order is free; `role` may
+// be left out.
+fn import_rows(body: &str) -> Result<Vec<ImportMemberRow>, ApiError> {
+    let mut records = csv::parse(body).map_err(ApiError::bad_request)?.into_iter();
+    let (_, header) = records
+        .next()
+        .ok_or_else(|| ApiError::bad_request("CSV body has no header row"))?;
+    let column = |name: &str| header.iter().position(|column| column.trim() == name);
+    let required = |name: &'static str| {
+        column(name).ok_or_else(|| {
+            ApiError::bad_request(format!("CSV header is missing the '{}' column", name))
+                .with_field("field", name)
+        })
+    };
+    let (name, age, grade, major) = (
+        required("name")?,
+        required("age")?,
+        required("grade")?,
+        required("major")?,
+    );
+    let role = column("role");
+
+    Ok(records
+        .map(|(line, fields)| {
+            let field = |index: usize| fields.get(index).cloned().unwrap_or_default();
+            ImportMemberRow {
+                line,
+                name: field(name),
+                age: field(age),
+                grade: field(grade),
+                major: field(major),
+                role: role.map(field),
+            }
+        })
+        .collect())
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/circle/{id}/members/import",
+    params(
+        ("id" = String, Path, description = "Circle id"),
+        ("mode" = Option<String>, Query, description = "`strict` (default) or `lenient`")
+    ),
+    request_body(content = String, content_type = "text/csv", description = "Header `name,age,grade,major[,role]` then one member per row"),
+    responses(
+        (status = 200, description = "Rows imported; in lenient mode `rejected` lists the skipped ones by line", body = ImportMembersResponseBody),
+        (status = 400, description = "Malformed CSV, or a row was rejected in strict mode"),
+        (status = 404, description = "Circle not found")
+    )
+)]
+pub async fn handle_import_members(
+    State(state): State<AppState>,
+    Path(path): Path<FetchCircleInputParam>,
+    Query(query): Query<ImportMembersQuery>,
+    body: String,
+) -> Result<Json<ImportMembersResponseBody>, ApiError> {
+    let import_members_input = ImportMembersInput::new(
+        path.id,
+        import_rows(&body)?,
+        query.mode == ImportMode::Strict,
+    );
+    let usecase = ImportMembersUsecase::new(state.circle_repository);
+    usecase
+        .execute(import_members_input)
+        .await
+        .map(ImportMembersResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct MoveMemberRequestBody {
+    pub to_circle_id: String,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct MoveMemberResponseBody {
+    pub from_circle_id: String,
+    pub to_circle_id: String,
+    pub member_id: String,
+}
+
+impl std::convert::From<MoveMemberOutput> for MoveMemberResponseBody {
+    fn from(
+        MoveMemberOutput {
+            from_circle_id,
+            to_circle_id,
+            member_id,
+        }: MoveMemberOutput,
+    ) -> Self {
+        MoveMemberResponseBody {
+            from_circle_id,
+            to_circle_id,
+            member_id,
+        }
+    }
+}
+
+impl std::convert::From<MoveMemberError> for ApiError {
+    fn from(error: MoveMemberError) -> Self {
+        match error {
+            MoveMemberError::CircleNotFound => {
+                ApiError::new(StatusCode::NOT_FOUND, "circle_not_found", error.to_string())
+            }
+            MoveMemberError::MemberNotFound => {
+                ApiError::new(StatusCode::NOT_FOUND, "member_not_found", error.to_string())
+            }
+            MoveMemberError::SameCircle | MoveMemberError::OwnerCannotMove => {
+                ApiError::bad_request(error.to_string())
+            }
+            MoveMemberError::Rejected(error) => {
+                ApiError::new(StatusCode::CONFLICT, "move_rejected", error.to_string())
+            }
+            MoveMemberError::Repository(error) => ApiError::from(error),
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/circle/{id}/members/{member_id}/move",
+    params(
+        ("id" = String, Path, description = "Circle the member leaves"),
+        ("member_id" = String, Path, description = "Member id")
+    ),
+    request_body = MoveMemberRequestBody,
+    responses(
+        (status = 200, description = "Member moved", body = MoveMemberResponseBody),
+        (status = 400, description = "Owner or same-circle move"),
+        (status = 404, description = "Circle or member not found"),
+        (status = 409, description = "Destination circle refused the member")
+    )
+)]
+pub async fn handle_move_member(
+    State(state): State<AppState>,
+    Path(path): Path<UpdateMemberInputParam>,
+    ApiJson(body): ApiJson<MoveMemberRequestBody>,
+) -> Result<Json<MoveMemberResponseBody>, ApiError> {
+    let move_member_input = MoveMemberInput::new(path.id, body.to_circle_id, path.member_id);
+    let usecase = MoveMemberUsecase::new(state.circle_repository);
+
+    usecase
+        .execute(move_member_input)
+        .await
+        .map(MoveMemberResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct MergeCirclesRequestBody {
+    pub source_circle_id: String,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct MergeCirclesResponseBody {
+    pub circle: FetcheCircleResponseBody,
+    pub source_circle_id: String,
+    // Source members the circle already had, by id or by name.
+    pub skipped: Vec<MemberOutput>,
+}
+
+impl std::convert::From<MergeCirclesOutput> for MergeCirclesResponseBody {
+    fn from(
+        MergeCirclesOutput {
+            circle,
+            source_circle_id,
+            skipped,
+        }: MergeCirclesOutput,
+    ) -> Self {
+        MergeCirclesResponseBody {
+            circle: FetcheCircleResponseBody::from(circle),
+            source_circle_id,
+            skipped,
+        }
+    }
+}
+
+impl std::convert::From<MergeCirclesError> for ApiError {
+    fn from(error: MergeCirclesError) -> Self {
+        match error {
+            MergeCirclesError::CircleNotFound => {
+                ApiError::new(StatusCode::NOT_FOUND, "circle_not_found", error.to_string())
+            }
+            MergeCirclesError::SameCircle => ApiError::bad_request(error.to_string()),
+            MergeCirclesError::Forbidden(error) => ApiError::from(anyhow::Error::new(error)),
+            MergeCirclesError::Rejected(error) => {
+                ApiError::new(StatusCode::CONFLICT, "merge_rejected", error.to_string())
+            }
+            MergeCirclesError::Repository(error) => ApiError::from(error),
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/circle/{id}/merge",
+    params(
+        ("id" = String, Path, description = "Circle that takes in the source's members"),
+        ("x-actor-id" = Option<String>, Header, description = "Id the caller acts as")
+    ),
+    request_body = MergeCirclesRequestBody,
+    responses(
+        (status = 200, description = "Members merged and the source circle deleted", body = MergeCirclesResponseBody),
+        (status = 400, description = "Source and target are the same circle"),
+        (status = 403, description = "Authorizer refused to update the target or delete the source"),
+        (status = 404, description = "Either circle not found"),
+        (status = 409, description = "Target circle refused the source's members")
+    )
+)]
+pub async fn handle_merge_circles(
+    State(state): State<AppState>,
+    Extension(authorizer): Extension<SharedAuthorizer>,
+    Actor(actor): Actor,
+    Path(path): Path<FetchCircleInputParam>,
+    ApiJson(body): ApiJson<MergeCirclesRequestBody>,
+) -> Result<Json<MergeCirclesResponseBody>, ApiError> {
+    let merge_circles_input =
+        MergeCirclesInput::new(path.id, body.source_circle_id).with_actor(actor);
+    let usecase = MergeCirclesUsecase::new(state.circle_repository).with_authorizer(authorizer);
+    usecase
+        .execute(merge_circles_input)
+        .await
+        .map(MergeCirclesResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct CloneCircleRequestBody {
+    pub circle_name: String,
+    #[serde(default)]
+    pub copy_members: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/circle/{id}/clone",
+    params(("id" = String, Path, description = "Circle to copy")),
+    request_body = CloneCircleRequestBody,
+    responses(
+        (status = 200, description = "Circle cloned with fresh ids", body = CreateCircleResponseBody),
+        (status = 409, description = "Circle name already taken")
+    )
+)]
+pub async fn handle_clone_circle(
+    State(state): State<AppState>,
+    Path(path): Path<FetchCircleInputParam>,
+    ApiJson(body): ApiJson<CloneCircleRequestBody>,
+) -> Result<Json<CreateCircleResponseBody>, ApiError> {
+    let clone_circle_input = CloneCircleInput::new(path.id, body.circle_name, body.copy_members);
+    let usecase = CloneCircleUsecase::new(state.circle_repository);
+    usecase
+        .execute(clone_circle_input)
+        .await
+        .map(CreateCircleResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteCircleInputParam {
+    id: String,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct DeleteCircleResponseBody {
+    pub circle_id: String,
+}
+
+impl std::convert::From<DeleteCircleOutput> for DeleteCircleResponseBody {
+    fn from(DeleteCircleOutput { circle_id }: DeleteCircleOutput) -> Self {
+        DeleteCircleResponseBody { circle_id }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/circle/{id}",
+    params(
+        ("id" = String, Path, description = "Circle id"),
+        ("x-actor-id" = Option<String>, Header, description = "Id the caller acts as")
+    ),
+    responses(
+        (status = 200, description = "Circle deleted", body = DeleteCircleResponseBody),
+        (status = 403, description = "The actor may not delete the circle"),
+        (status = 404, description = "Circle not found")
+    )
+)]
+pub async fn handle_delete_circle(
+    State(state): State<AppState>,
+    Extension(authorizer): Extension<SharedAuthorizer>,
+    Actor(actor): Actor,
+    Path(path): Path<DeleteCircleInputParam>,
+) -> Result<Json<DeleteCircleResponseBody>, ApiError> {
+    let delete_circle_input = DeleteCircleInput::new(path.id).with_actor(actor);
+    let usecase = DeleteCircleUsecase::new(state.circle_repository).with_authorizer(authorizer);
+    usecase
+        .execute(delete_circle_input)
+        .await
+        .map(DeleteCircleResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RestoreCircleInputParam {
+    id: String,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct RestoreCircleResponseBody {
+    pub circle_id: String,
+}
+
+impl std::convert::From<RestoreCircleOutput> for RestoreCircleResponseBody {
+    fn from(RestoreCircleOutput { circle_id }: RestoreCircleOutput) -> Self {
+        RestoreCircleResponseBody { circle_id }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/circle/{id}/restore",
+    params(("id" = String, Path, description = "Circle id")),
+    responses(
+        (status = 200, description = "Circle restored", body = RestoreCircleResponseBody),
+        (status = 404, description = "No deleted circle with this id, or its owner now belongs to another circle")
+    )
+)]
+pub async fn handle_restore_circle(
+    State(state): State<AppState>,
+    Path(path): Path<RestoreCircleInputParam>,
+) -> Result<Json<RestoreCircleResponseBody>, ApiError> {
+    let restore_circle_input = RestoreCircleInput::new(path.id);
+    let usecase = RestoreCircleUsecase::new(state.circle_repository);
+    usecase
+        .execute(restore_circle_input)
+        .await
+        .map(RestoreCircleResponseBody::from)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+// Wipes every circle and member, soft-deleted and archived ones included, for
+// local development and CI. `TRUNCATE` would commit implicitly, so the tables
+// are cleared with `DELETE` in one transaction.
+#[utoipa::path(
+    post,
+    path = "/admin/reset",
+    responses(
+        (status = 204, description = "All circles and members removed"),
+        (status = 403, description = "ALLOW_RESET is not set")
+    )
+)]
+#[tracing::instrument(name = "handle_admin_reset", skip(state))]
+pub async fn handle_admin_reset(State(state): State<AppState>) -> Result<StatusCode, ApiError> {
+    if !reset_allowed() {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "forbidden",
+            "Reset is disabled; set ALLOW_RESET=true to enable it",
+        ));
+    }
+
+    let reset = async {
+        let mut tx = state.pool.begin().await?;
+        sqlx::query("DELETE FROM members").execute(&mut *tx).await?;
+        sqlx::query("DELETE FROM circles").execute(&mut *tx).await?;
+        sqlx::query("DELETE FROM members_archive")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM circles_archive")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM membership_history")
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    };
+    reset.await.map_err(|e| {
+        tracing::error!("Failed to reset database: {:?}", e);
+        ApiError::internal("Failed to reset database")
+    })?;
+    tracing::warn!("database reset");
+    Ok(StatusCode::NO_CONTENT)
 }
 
 #[tracing::instrument(name = "handle_get_test", skip(state))]
@@ -198,10 +1818,10 @@ pub async fn handle_get_test(State(state): State<AppState>) -> impl IntoResponse
     };
 
     for row in circle_rows {
-        let id = row.get::<i64, _>("id");
+        let id = row.get::<String, _>("id");
         let name = row.get::<String, _>("name");
         let capacity = row.get::<i64, _>("capacity");
-        let owner_id = row.get::<i64, _>("owner_id");
+        let owner_id = row.get::<String, _>("owner_id");
         tracing::info!(
             "id: {:?} circle: {:?} capacity: {:?} owner_id: {:?}",
             id,
@@ -230,6 +1850,15 @@ pub async fn handle_get_test(State(state): State<AppState>) -> impl IntoResponse
     (StatusCode::OK).into_response()
 }
 
+// Router fallback, so unknown paths get the same error body as everything else.
+pub async fn handle_not_found(uri: axum::http::Uri) -> ApiError {
+    ApiError::new(
+        StatusCode::NOT_FOUND,
+        "not_found",
+        format!("No route for {}", uri.path()),
+    )
+}
+
 #[tracing::instrument(name = "handle_debug", skip())]
 pub async fn handle_debug() -> impl IntoResponse {
     tracing::info!("info");
@@ -237,5 +1866,5 @@ pub async fn handle_debug() -> impl IntoResponse {
     tracing::warn!("warn");
     tracing::debug!("debug");
     tracing::trace!("trace");
-    (StatusCode::OK).into_response()
-}
\ No newline at end of file
+    Json(serde_json::json!({ "logged": true }))
+}
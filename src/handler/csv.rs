@@ -0,0 +1,207 @@
+use usecase::fetch_circle::{FetchCircleOutput, MemberOutput};
+
+pub const CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+
+pub const CIRCLE_HEADER: &[&str] = &[
+    "circle_id",
+    "circle_name",
+    "capacity",
+    "owner_id",
+    "owner_name",
+    "member_count",
+];
+
+// With `?members=true` there is one row per member, owner first.
+pub const MEMBER_HEADER: &[&str] = &[
+    "circle_id",
+    "circle_name",
+    "member_id",
+    "name",
+    "age",
+    "grade",
+    "major",
+    "role",
+];
+
+// RFC 4180: a field holding a comma, quote or line break is quoted, with
+// quotes doubled.
+pub fn write_record<S: AsRef<str>>(out: &mut String, fields: &[S]) {
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        let field = field.as_ref();
+        if field.contains([',', '"', '\r', '\n']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+pub fn header(members: bool) -> String {
+    let mut out = String::new();
+    write_record(
+        &mut out,
+        if members {
+            MEMBER_HEADER
+        } else {
+            CIRCLE_HEADER
+        },
+    );
+    out
+}
+
+pub fn rows(circles: &[FetchCircleOutput], members: bool) -> String {
+    let mut out = String::new();
+    for circle in circles {
+        if !members {
+            write_record(
+                &mut out,
+                &[
+                    circle.circle_id.clone(),
+                    circle.circle_name.clone(),
+                    circle.capacity.to_string(),
+                    circle.owner.id.clone(),
+                    circle.owner.name.clone(),
+                    circle.members.len().to_string(),
+                ],
+            );
+            continue;
+        }
+        for member in std::iter::once(&circle.owner).chain(&circle.members) {
+            write_record(&mut out, &member_record(circle, member));
+        }
+    }
+    out
+}
+
+fn member_record(circle: &FetchCircleOutput, member: &MemberOutput) -> [String; 8] {
+    [
+        circle.circle_id.clone(),
+        circle.circle_name.clone(),
+        member.id.clone(),
+        member.name.clone(),
+        member.age.to_string(),
+        member.grade.as_i16().to_string(),
+        member.major.clone(),
+        member.role.as_str().to_string(),
+    ]
+}
+
+// Splits RFC 4180 text into records of fields, each paired with the 1-based
+// line it starts on. Quoted fields may hold commas, doubled quotes and line
+// breaks; blank lines are skipped.
+pub fn parse(input: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = input.chars().peekable();
+    let mut line = 1;
+    let mut start = 1;
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            '\n' if quoted => {
+                line += 1;
+                field.push(c);
+            }
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.len() > 1 || !fields[0].is_empty() {
+                    records.push((start, std::mem::take(&mut fields)));
+                }
+                fields.clear();
+                line += 1;
+                start = line;
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("line {}: unterminated quoted field", start));
+    }
+    if !fields.is_empty() || !field.is_empty() {
+        fields.push(field);
+        records.push((start, fields));
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use domain::aggregate::{
+        circle::Circle,
+        member::Member,
+        value_object::{capacity::Capacity, grade::Grade, major::Major},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_write_record_quotes_special_characters() {
+        let mut out = String::new();
+        write_record(&mut out, &["plain", "a,b", "say \"hi\"", "two\nlines"]);
+        assert_eq!(out, "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\"\r\n");
+    }
+
+    #[test]
+    fn test_parse() {
+        let records =
+            parse("name,age\r\n\"Lennon, John\",21\n\n\"two\nlines\",\"a \"\"b\"\"\"\nlast,1")
+                .unwrap();
+        assert_eq!(
+            records,
+            vec![
+                (1, vec!["name".to_string(), "age".to_string()]),
+                (2, vec!["Lennon, John".to_string(), "21".to_string()]),
+                (4, vec!["two\nlines".to_string(), "a \"b\"".to_string()]),
+                (6, vec!["last".to_string(), "1".to_string()]),
+            ]
+        );
+        assert!(parse("name\n\"open").is_err());
+    }
+
+    #[test]
+    fn test_rows() -> anyhow::Result<()> {
+        let owner = Member::new("John, Jr.".to_string(), 21, Grade::Third, Major::Music);
+        let mut circle = Circle::new("Music club".to_string(), owner, Capacity::new(10)?)?;
+        circle.add_member(Member::new(
+            "Paul".to_string(),
+            19,
+            Grade::First,
+            Major::Art,
+        ))?;
+        let output = FetchCircleOutput::from(circle.clone());
+
+        assert_eq!(
+            header(false) + &rows(std::slice::from_ref(&output), false),
+            format!(
+                "circle_id,circle_name,capacity,owner_id,owner_name,member_count\r\n\
+                 {},Music club,10,{},\"John, Jr.\",1\r\n",
+                circle.id, circle.owner.id
+            )
+        );
+        assert_eq!(
+            rows(&[output], true),
+            format!(
+                "{0},Music club,{1},\"John, Jr.\",21,3,Music,owner\r\n\
+                 {0},Music club,{2},Paul,19,1,Art,member\r\n",
+                circle.id, circle.owner.id, circle.members[0].id
+            )
+        );
+        Ok(())
+    }
+}
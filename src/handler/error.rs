@@ -0,0 +1,206 @@
+use axum::{
+    extract::rejection::JsonRejection,
+    http::{header::RETRY_AFTER, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use domain::{
+    aggregate::value_object::capacity::CapacityError,
+    error::DomainError,
+    interface::circle_repository_interface::{
+        ConflictError, DataIntegrityError, DeadlineExceededError, NotFoundError, UnavailableError,
+    },
+};
+use serde_json::{json, Map, Value};
+use usecase::{authorization::ForbiddenError, validation::ValidationError};
+
+// Renders as `{ "error": { "code": ..., "message": ..., ...fields } }`.
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+    fields: Map<String, Value>,
+    retry_after: Option<u64>,
+}
+
+// How long clients are told to wait out a database outage.
+const UNAVAILABLE_RETRY_AFTER_SECS: u64 = 5;
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+            fields: Map::new(),
+            retry_after: None,
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "bad_request", message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal_error", message)
+    }
+
+    pub fn with_field(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.fields.insert(key.to_string(), value.into());
+        self
+    }
+
+    // Sent as the `Retry-After` header, in seconds.
+    pub fn with_retry_after(mut self, seconds: u64) -> Self {
+        self.retry_after = Some(seconds);
+        self
+    }
+}
+
+// Broken rules are the client's to fix, so they answer 400 with a code per
+// rule; a lookup that misses is a 404, and data no aggregate could produce
+// is a server fault.
+impl std::convert::From<DomainError> for ApiError {
+    fn from(error: DomainError) -> Self {
+        let (status, code) = match &error {
+            DomainError::Capacity(_) => (StatusCode::BAD_REQUEST, "invalid_capacity"),
+            DomainError::CapacityExceeded => (StatusCode::BAD_REQUEST, "capacity_exceeded"),
+            DomainError::TooManyMembers { .. } => (StatusCode::BAD_REQUEST, "too_many_members"),
+            DomainError::InvalidGrade(_) => (StatusCode::BAD_REQUEST, "invalid_grade"),
+            DomainError::InvalidRole(_) => (StatusCode::BAD_REQUEST, "invalid_role"),
+            DomainError::OwnerGradeTooLow { .. } | DomainError::OwnerTooYoung { .. } => {
+                (StatusCode::BAD_REQUEST, "owner_ineligible")
+            }
+            DomainError::FourthGradeCannotJoin => {
+                (StatusCode::BAD_REQUEST, "fourth_grade_cannot_join")
+            }
+            DomainError::AlreadyMember => (StatusCode::BAD_REQUEST, "already_member"),
+            DomainError::DuplicateMemberName(_) => {
+                (StatusCode::BAD_REQUEST, "duplicate_member_name")
+            }
+            DomainError::OwnerAlreadyAssigned => {
+                (StatusCode::BAD_REQUEST, "owner_already_assigned")
+            }
+            DomainError::OwnerRoleImmutable => (StatusCode::BAD_REQUEST, "owner_role_immutable"),
+            DomainError::OwnerCannotBeRemoved => {
+                (StatusCode::BAD_REQUEST, "owner_cannot_be_removed")
+            }
+            DomainError::OwnerNotMember => (StatusCode::NOT_FOUND, "owner_not_member"),
+            DomainError::MemberNotFound => (StatusCode::NOT_FOUND, "member_not_found"),
+            DomainError::InconsistentRoster(_) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "internal_error")
+            }
+        };
+        Self::new(status, code, error.to_string())
+    }
+}
+
+impl std::convert::From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<DomainError>() {
+            Ok(error) => return Self::from(error),
+            Err(error) => error,
+        };
+        if error.is::<CapacityError>() {
+            return Self::new(
+                StatusCode::BAD_REQUEST,
+                "invalid_capacity",
+                error.to_string(),
+            );
+        }
+        if let Some(error) = error.downcast_ref::<ValidationError>() {
+            return Self::bad_request(error.to_string()).with_field("field", error.path.as_str());
+        }
+        if error.is::<ForbiddenError>() {
+            return Self::new(StatusCode::FORBIDDEN, "forbidden", error.to_string());
+        }
+        if error.is::<NotFoundError>() {
+            return Self::new(StatusCode::NOT_FOUND, "not_found", error.to_string());
+        }
+        if error.is::<ConflictError>() {
+            return Self::new(StatusCode::CONFLICT, "conflict", error.to_string());
+        }
+        // A stable code so corrupt rows can be alerted on apart from other 500s.
+        if let Some(error) = error.downcast_ref::<DataIntegrityError>() {
+            return Self::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "data_integrity_error",
+                error.to_string(),
+            )
+            .with_field("circle_id", error.circle_id.as_str())
+            .with_field("owner_id", error.owner_id.as_str());
+        }
+        // Same answer the timeout middleware gives when the deadline fires
+        // outside the repository.
+        if error.is::<DeadlineExceededError>() {
+            return Self::new(StatusCode::GATEWAY_TIMEOUT, "timeout", error.to_string());
+        }
+        if error.is::<UnavailableError>() {
+            return Self::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "unavailable",
+                error.to_string(),
+            )
+            .with_retry_after(UNAVAILABLE_RETRY_AFTER_SECS);
+        }
+        ApiError::internal(error.to_string())
+    }
+}
+
+// axum keeps serde's error, with the path to the failing field, as the source
+// of its own.
+fn json_path_error(
+    rejection: &JsonRejection,
+) -> Option<&serde_path_to_error::Error<serde_json::Error>> {
+    let mut source = std::error::Error::source(rejection);
+    while let Some(error) = source {
+        if let Some(path_error) = error.downcast_ref() {
+            return Some(path_error);
+        }
+        source = error.source();
+    }
+    None
+}
+
+impl std::convert::From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        let status = rejection.status();
+        if status != StatusCode::BAD_REQUEST && status != StatusCode::UNPROCESSABLE_ENTITY {
+            return Self::new(status, "invalid_body", rejection.body_text());
+        }
+
+        match json_path_error(&rejection) {
+            Some(error) => {
+                let path = error.path().to_string();
+                let message = match &rejection {
+                    JsonRejection::JsonSyntaxError(_) => {
+                        format!("Malformed JSON body: {}", error.inner())
+                    }
+                    _ if path == "." => format!("Invalid request body: {}", error.inner()),
+                    _ => format!("Invalid value for `{}`: {}", path, error.inner()),
+                };
+                let api_error = Self::bad_request(message);
+                if path == "." {
+                    api_error
+                } else {
+                    api_error.with_field("field", path)
+                }
+            }
+            None => Self::bad_request(rejection.body_text()),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let mut error = self.fields;
+        error.insert("code".to_string(), Value::from(self.code));
+        error.insert("message".to_string(), Value::from(self.message));
+        let mut response = (self.status, Json(json!({ "error": error }))).into_response();
+        if let Some(seconds) = self.retry_after {
+            response.headers_mut().insert(RETRY_AFTER, seconds.into());
+        }
+        response
+    }
+}
@@ -0,0 +1,63 @@
+use serde_json::{Map, Value};
+
+// Splits a comma-separated `fields` list, skipping blank entries. A name not in
+// `allowed` is returned as the error.
+pub fn parse<'a>(fields: &'a str, allowed: &[&str]) -> Result<Vec<&'a str>, String> {
+    let mut names = Vec::new();
+    for name in fields.split(',').map(str::trim) {
+        if name.is_empty() || names.contains(&name) {
+            continue;
+        }
+        if !allowed.contains(&name) {
+            return Err(name.to_string());
+        }
+        names.push(name);
+    }
+    Ok(names)
+}
+
+// Keeps only the named top-level keys; no names keeps the whole object.
+pub fn project(value: Value, names: &[&str]) -> Value {
+    match value {
+        Value::Object(mut object) if !names.is_empty() => Value::Object(
+            names
+                .iter()
+                .filter_map(|name| object.remove(*name).map(|field| (name.to_string(), field)))
+                .collect::<Map<_, _>>(),
+        ),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const ALLOWED: &[&str] = &["circle_id", "circle_name", "capacity", "members"];
+
+    fn circle() -> Value {
+        json!({ "circle_id": "1", "circle_name": "club", "capacity": 10, "members": [] })
+    }
+
+    #[test]
+    fn test_project_keeps_requested_fields() -> Result<(), String> {
+        let names = parse("circle_name, capacity,circle_name", ALLOWED)?;
+        assert_eq!(names, vec!["circle_name", "capacity"]);
+        assert_eq!(
+            project(circle(), &names),
+            json!({ "circle_name": "club", "capacity": 10 })
+        );
+        assert_eq!(project(circle(), &parse("", ALLOWED)?), circle());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_field() {
+        assert_eq!(
+            parse("circle_name,owner_email", ALLOWED),
+            Err("owner_email".to_string())
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use domain::aggregate::value_object::capacity::Capacity;
+use serde::{de, Deserialize, Deserializer};
+
+use super::merge_patch::Patch;
+
+// Numbers some clients send quoted, e.g. `"grade": "3"`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrString {
+    Number(i16),
+    String(String),
+}
+
+impl NumberOrString {
+    fn into_i16<E: de::Error>(self) -> Result<i16, E> {
+        match self {
+            NumberOrString::Number(value) => Ok(value),
+            NumberOrString::String(value) => value
+                .trim()
+                .parse()
+                .map_err(|_| E::custom(format!("expected an integer, got {:?}", value))),
+        }
+    }
+}
+
+// For `#[serde(deserialize_with = "...")]`: accepts `3` or `"3"`.
+pub fn i16_or_string<'de, D>(deserializer: D) -> Result<i16, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    NumberOrString::deserialize(deserializer)?.into_i16()
+}
+
+// As `i16_or_string` for optional fields; pair with `#[serde(default)]`.
+pub fn option_i16_or_string<'de, D>(deserializer: D) -> Result<Option<i16>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<NumberOrString>::deserialize(deserializer)?
+        .map(NumberOrString::into_i16)
+        .transpose()
+}
+
+// Capacities are read as any integer so one past `i16` is reported against
+// the real limits instead of as serde's "expected i16"; values that do fit are
+// left for `Capacity` to range-check.
+fn capacity_in_range<E: de::Error>(value: i64) -> Result<i16, E> {
+    i16::try_from(value).map_err(|_| {
+        if value > 0 {
+            E::custom(format!("capacity exceeds maximum of {}", Capacity::MAX))
+        } else {
+            E::custom(format!("capacity is below minimum of {}", Capacity::MIN))
+        }
+    })
+}
+
+// For an optional `capacity`; pair with `#[serde(default)]`.
+pub fn option_capacity<'de, D>(deserializer: D) -> Result<Option<i16>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<i64>::deserialize(deserializer)?
+        .map(capacity_in_range)
+        .transpose()
+}
+
+// As `option_capacity` for a merge patch member.
+pub fn patch_capacity<'de, D>(deserializer: D) -> Result<Patch<i16>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Option::<i64>::deserialize(deserializer)? {
+        Some(value) => Patch::Value(capacity_in_range(value)?),
+        None => Patch::Null,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Document {
+        #[serde(deserialize_with = "i16_or_string")]
+        grade: i16,
+        #[serde(default, deserialize_with = "option_i16_or_string")]
+        next_grade: Option<i16>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct CapacityDocument {
+        #[serde(default, deserialize_with = "option_capacity")]
+        capacity: Option<i16>,
+        #[serde(default, deserialize_with = "patch_capacity")]
+        patched: Patch<i16>,
+    }
+
+    fn parse(json: &str) -> Result<Document, serde_json::Error> {
+        serde_json::from_str::<Document>(json)
+    }
+
+    #[test]
+    fn test_number_or_string() -> anyhow::Result<()> {
+        assert_eq!(parse(r#"{"grade":3}"#)?.grade, 3);
+        assert_eq!(parse(r#"{"grade":"3"}"#)?.grade, 3);
+        assert!(parse(r#"{"grade":"third"}"#).is_err());
+        assert!(parse(r#"{"grade":3.5}"#).is_err());
+
+        assert_eq!(parse(r#"{"grade":3}"#)?.next_grade, None);
+        assert_eq!(parse(r#"{"grade":3,"next_grade":null}"#)?.next_grade, None);
+        assert_eq!(
+            parse(r#"{"grade":3,"next_grade":"4"}"#)?.next_grade,
+            Some(4)
+        );
+        assert_eq!(parse(r#"{"grade":3,"next_grade":4}"#)?.next_grade, Some(4));
+        Ok(())
+    }
+
+    #[test]
+    fn test_capacity_outside_i16() -> anyhow::Result<()> {
+        let parse = serde_json::from_str::<CapacityDocument>;
+
+        assert_eq!(parse(r#"{"capacity":10}"#)?.capacity, Some(10));
+        assert_eq!(parse(r#"{"capacity":2000}"#)?.capacity, Some(2000));
+        assert_eq!(parse("{}")?.patched, Patch::Absent);
+        assert_eq!(parse(r#"{"patched":null}"#)?.patched, Patch::Null);
+        assert_eq!(parse(r#"{"patched":10}"#)?.patched, Patch::Value(10));
+
+        let error = parse(r#"{"capacity":40000}"#).unwrap_err().to_string();
+        assert!(error.starts_with("capacity exceeds maximum of 1000"));
+        let error = parse(r#"{"patched":-40000}"#).unwrap_err().to_string();
+        assert!(error.starts_with("capacity is below minimum of 3"));
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Deserializer};
+
+// One member of an RFC 7386 merge patch document. Pair with `#[serde(default)]`
+// so a missing key stays `Absent` instead of failing to deserialize.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Patch<T> {
+    #[default]
+    Absent,
+    Null,
+    Value(T),
+}
+
+impl<'de, T> Deserialize<'de> for Patch<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<T>::deserialize(deserializer).map(|value| match value {
+            Some(value) => Patch::Value(value),
+            None => Patch::Null,
+        })
+    }
+}
+
+impl<T> Patch<T> {
+    // For members that cannot be cleared: `Absent` keeps the current value,
+    // `Null` is reported as `Err(())`.
+    pub fn required(self) -> Result<Option<T>, ()> {
+        match self {
+            Patch::Absent => Ok(None),
+            Patch::Null => Err(()),
+            Patch::Value(value) => Ok(Some(value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Document {
+        #[serde(default)]
+        name: Patch<String>,
+    }
+
+    fn parse(json: &str) -> Patch<String> {
+        serde_json::from_str::<Document>(json).unwrap().name
+    }
+
+    #[test]
+    fn test_absent_null_and_value_are_distinct() {
+        assert_eq!(parse("{}"), Patch::Absent);
+        assert_eq!(parse(r#"{"name":null}"#), Patch::Null);
+        assert_eq!(
+            parse(r#"{"name":"club"}"#),
+            Patch::Value("club".to_string())
+        );
+    }
+
+    #[test]
+    fn test_required() {
+        assert_eq!(Patch::<i16>::Absent.required(), Ok(None));
+        assert_eq!(Patch::<i16>::Null.required(), Err(()));
+        assert_eq!(Patch::Value(3).required(), Ok(Some(3)));
+    }
+}
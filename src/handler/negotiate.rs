@@ -0,0 +1,89 @@
+use axum::{
+    http::{
+        header::{ACCEPT, CONTENT_TYPE, VARY},
+        HeaderMap,
+    },
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+pub const MSGPACK: &str = "application/msgpack";
+
+// Body encodings a client can ask for through `Accept`; anything else,
+// including no header at all, gets JSON.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    MessagePack,
+}
+
+impl Format {
+    pub fn from_accept(headers: &HeaderMap) -> Self {
+        let wants_msgpack = headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|media_type| media_type.split(';').next())
+            .any(|media_type| media_type.trim().eq_ignore_ascii_case(MSGPACK));
+        if wants_msgpack {
+            Format::MessagePack
+        } else {
+            Format::Json
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            Format::MessagePack => MSGPACK,
+        }
+    }
+
+    // MessagePack maps keep field names so the payload mirrors the JSON one.
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, anyhow::Error> {
+        Ok(match self {
+            Format::Json => serde_json::to_vec(value)?,
+            Format::MessagePack => rmp_serde::to_vec_named(value)?,
+        })
+    }
+
+    pub fn respond<T: Serialize>(self, value: &T) -> Result<Response, anyhow::Error> {
+        let body = self.encode(value)?;
+        Ok((
+            [(CONTENT_TYPE, self.content_type()), (VARY, ACCEPT.as_str())],
+            body,
+        )
+            .into_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_from_accept() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(Format::from_accept(&headers), Format::Json);
+
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("text/html, application/msgpack;q=0.9"),
+        );
+        assert_eq!(Format::from_accept(&headers), Format::MessagePack);
+
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        assert_eq!(Format::from_accept(&headers), Format::Json);
+    }
+
+    #[test]
+    fn test_msgpack_round_trip() -> anyhow::Result<()> {
+        let value = serde_json::json!({ "circle_id": "abc", "capacity": 10 });
+        let encoded = Format::MessagePack.encode(&value)?;
+        assert_eq!(rmp_serde::from_slice::<serde_json::Value>(&encoded)?, value);
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+pub mod catch_panic;
+pub mod pretty;
+pub mod rate_limit;
+pub mod timeout;
@@ -0,0 +1,39 @@
+use std::{any::Any, backtrace::Backtrace, cell::RefCell, sync::Once};
+
+use axum::response::{IntoResponse, Response};
+
+use crate::handler::ApiError;
+
+thread_local! {
+    static BACKTRACE: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+}
+
+// By the time `CatchPanicLayer` sees a panic the stack has unwound, so the
+// hook captures the backtrace where it was raised and `handle_panic`, which
+// runs on the same thread, takes it from there. The previous hook still runs,
+// so panics outside a handler print as they always did.
+pub fn capture_backtraces() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            BACKTRACE.with(|backtrace| *backtrace.borrow_mut() = Some(Backtrace::force_capture()));
+            previous(info);
+        }));
+    });
+}
+
+// For `CatchPanicLayer::custom`: the panic message and backtrace go to the log
+// only, the client gets the usual 500 body.
+pub fn handle_panic(panic: Box<dyn Any + Send + 'static>) -> Response {
+    let message = panic
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| panic.downcast_ref::<&str>().copied())
+        .unwrap_or("non-string panic payload");
+    match BACKTRACE.with(|backtrace| backtrace.borrow_mut().take()) {
+        Some(backtrace) => tracing::error!("handler panicked: {}\n{}", message, backtrace),
+        None => tracing::error!("handler panicked: {}", message),
+    }
+    ApiError::internal("Internal server error").into_response()
+}
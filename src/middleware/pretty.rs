@@ -0,0 +1,55 @@
+use axum::{
+    extract::{Query, Request},
+    http::{
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+        Method,
+    },
+    middleware::Next,
+    response::Response,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct PrettyQuery {
+    #[serde(default)]
+    pretty: bool,
+}
+
+// Re-indents the JSON a GET answers with when `?pretty=true` is passed, for
+// reading responses by hand. Handlers never see the parameter, and anything
+// that isn't JSON, such as MessagePack or CSV, passes through as it is. The
+// ETag, where one is sent, still describes the compact body.
+pub async fn pretty(request: Request, next: Next) -> Response {
+    let requested = request.method() == Method::GET
+        && Query::<PrettyQuery>::try_from_uri(request.uri()).is_ok_and(|Query(query)| query.pretty);
+    let response = next.run(request).await;
+    if !requested || !is_json(&response) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            tracing::warn!("could not read response body to pretty-print: {}", error);
+            return Response::from_parts(parts, axum::body::Body::empty());
+        }
+    };
+    let pretty = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .and_then(|value| serde_json::to_string_pretty(&value));
+    match pretty {
+        Ok(pretty) => {
+            parts.headers.remove(CONTENT_LENGTH);
+            Response::from_parts(parts, axum::body::Body::from(pretty))
+        }
+        Err(_) => Response::from_parts(parts, axum::body::Body::from(bytes)),
+    }
+}
+
+fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::handler::ApiError;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    by_ip: HashMap<IpAddr, Bucket>,
+    swept_at: Instant,
+}
+
+// A token bucket per client IP: each bucket holds up to `capacity` tokens and
+// regains them at `capacity` per minute.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    buckets: Arc<Mutex<Buckets>>,
+    capacity: f64,
+    refill_per_sec: f64,
+}
+
+impl RateLimiter {
+    pub fn per_minute(requests: u32) -> Self {
+        let capacity = f64::from(requests.max(1));
+        Self {
+            buckets: Arc::new(Mutex::new(Buckets {
+                by_ip: HashMap::new(),
+                swept_at: Instant::now(),
+            })),
+            capacity,
+            refill_per_sec: capacity / 60.0,
+        }
+    }
+
+    // Takes a token for `ip`, or returns how long until one is available.
+    fn try_acquire(&self, ip: IpAddr) -> Result<(), Duration> {
+        self.try_acquire_at(ip, Instant::now())
+    }
+
+    // A bucket that has refilled to capacity is no different from a new one,
+    // so once a minute those are dropped; otherwise every client ever seen
+    // would keep its entry.
+    fn try_acquire_at(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().expect("lock rate limit buckets");
+        if now.duration_since(buckets.swept_at) >= Duration::from_secs(60) {
+            buckets.by_ip.retain(|_, bucket| {
+                let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+                bucket.tokens + elapsed * self.refill_per_sec < self.capacity
+            });
+            buckets.swept_at = now;
+        }
+        let bucket = buckets.by_ip.entry(ip).or_insert(Bucket {
+            tokens: self.capacity,
+            refilled_at: now,
+        });
+
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.refill_per_sec,
+            ))
+        }
+    }
+}
+
+pub async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+    match limiter.try_acquire(ip) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            tracing::warn!("rate limit exceeded for {}", ip);
+            ApiError::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                "rate_limited",
+                "Too many requests, slow down",
+            )
+            .with_retry_after(retry_after.as_secs().max(1))
+            .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        time::{Duration, Instant},
+    };
+
+    use super::RateLimiter;
+
+    #[test]
+    fn test_refilled_buckets_are_pruned() {
+        let limiter = RateLimiter::per_minute(60);
+        let idle = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let busy = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+        let start = Instant::now();
+        assert!(limiter.try_acquire_at(idle, start).is_ok());
+        for _ in 0..60 {
+            assert!(limiter
+                .try_acquire_at(busy, start + Duration::from_secs(30))
+                .is_ok());
+        }
+
+        // Past the minute `idle` is full again and goes; `busy` has only
+        // regained about half its tokens, so it stays.
+        let newcomer = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 3));
+        assert!(limiter
+            .try_acquire_at(newcomer, start + Duration::from_secs(61))
+            .is_ok());
+        let buckets = limiter.buckets.lock().unwrap();
+        let mut ips = buckets.by_ip.keys().copied().collect::<Vec<_>>();
+        ips.sort();
+        assert_eq!(ips, vec![busy, newcomer]);
+    }
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use infrastructure::deadline;
+
+use crate::handler::ApiError;
+
+// Used instead of `tower_http::timeout::TimeoutLayer`, which answers 408 with
+// an empty body; a stalled handler here gets a 504 in the usual error shape.
+// The deadline is also handed down to the repository, which stops waiting on
+// its queries at the same instant.
+pub async fn timeout(State(limit): State<Duration>, request: Request, next: Next) -> Response {
+    let path = request.uri().path().to_string();
+    let deadline = tokio::time::Instant::now() + limit;
+    match deadline::scope(
+        deadline,
+        tokio::time::timeout_at(deadline, next.run(request)),
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::warn!("request to {} timed out after {:?}", path, limit);
+            ApiError::new(
+                StatusCode::GATEWAY_TIMEOUT,
+                "timeout",
+                format!("Request did not complete within {:?}", limit),
+            )
+            .into_response()
+        }
+    }
+}
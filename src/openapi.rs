@@ -0,0 +1,83 @@
+use axum::Json;
+use utoipa::OpenApi;
+
+use crate::handler;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        handler::handle_create_circle,
+        handler::handle_upsert_circle,
+        handler::handle_bulk_create_circles,
+        handler::handle_clone_circle,
+        handler::handle_fetch_circle,
+        handler::handle_fetch_circle_owner,
+        handler::handle_fetch_circle_stats,
+        handler::handle_search_members,
+        handler::handle_fetch_member_circle,
+        handler::handle_fetch_owned_circles,
+        handler::handle_fetch_membership_history,
+        handler::handle_fetch_full_circles,
+        handler::handle_batch_get_circles,
+        handler::handle_fetch_archived_circles,
+        handler::handle_fetch_all,
+        handler::handle_export_circles_csv,
+        handler::handle_stream_circles,
+        handler::handle_search_circles,
+        handler::handle_fetch_stats,
+        handler::handle_update_circle,
+        handler::handle_patch_circle,
+        handler::handle_update_member,
+        handler::handle_set_members,
+        handler::handle_import_members,
+        handler::handle_move_member,
+        handler::handle_merge_circles,
+        handler::handle_delete_circle,
+        handler::handle_restore_circle,
+        handler::handle_admin_reset,
+    ),
+    components(schemas(
+        handler::CreateCircleRequestBody,
+        handler::CreateMemberRequestBody,
+        handler::CreateCircleResponseBody,
+        handler::BulkCreateCircleResponseBody,
+        handler::CloneCircleRequestBody,
+        handler::FetcheCircleResponseBody,
+        handler::FetchAllResponseBody,
+        handler::OwnedCirclesResponseBody,
+        handler::MembershipHistoryResponseBody,
+        handler::FullCirclesResponseBody,
+        handler::BatchGetCirclesRequestBody,
+        handler::BatchGetCirclesResponseBody,
+        handler::ArchivedCircleResponseBody,
+        handler::ArchivedCirclesResponseBody,
+        handler::SearchCirclesResponseBody,
+        handler::SearchMembersResponseBody,
+        handler::FetchStatsResponseBody,
+        handler::FetchCircleStatsResponseBody,
+        handler::UpdateCircleRequestBody,
+        handler::PatchCircleRequestBody,
+        handler::UpdateCircleResponseBody,
+        handler::UpdateMemberRequestBody,
+        handler::UpdateMemberResponseBody,
+        handler::SetMemberRequestBody,
+        handler::SetMembersResponseBody,
+        handler::ImportMembersResponseBody,
+        handler::MoveMemberRequestBody,
+        handler::MoveMemberResponseBody,
+        handler::MergeCirclesRequestBody,
+        handler::MergeCirclesResponseBody,
+        handler::DeleteCircleResponseBody,
+        handler::RestoreCircleResponseBody,
+        usecase::fetch_circle::MemberOutput,
+        usecase::fetch_membership_history::MembershipEventOutput,
+        usecase::search_circle::CircleSummaryOutput,
+        usecase::import_members::RejectedRow,
+        usecase::update_circle::ChangedField,
+    ))
+)]
+pub struct ApiDoc;
+
+pub async fn handle_get_openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
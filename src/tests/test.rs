@@ -56,7 +56,7 @@ mod tests {
             .oneshot(
                 axum::http::Request::builder()
                     .method("POST")
-                    .uri("/circle")
+                    .uri("/v1/circle")
                     .header(CONTENT_TYPE, "application/json")
                     .body(axum::body::Body::new(serde_json::to_string(
                         &CreateCircleRequestBody {
@@ -111,7 +111,7 @@ mod tests {
             .oneshot(
                 axum::http::Request::builder()
                     .method("GET")
-                    .uri(format!("/circle/{}", unexist_circle_id))
+                    .uri(format!("/v1/circle/{}", unexist_circle_id))
                     .body(axum::body::Body::empty())?,
             )
             .await?;
@@ -129,7 +129,7 @@ mod tests {
             .oneshot(
                 axum::http::Request::builder()
                     .method("GET")
-                    .uri(format!("/circle/{}", circle_id))
+                    .uri(format!("/v1/circle/{}", circle_id))
                     .body(axum::body::Body::empty())?,
             )
             .await?;
@@ -163,7 +163,7 @@ mod tests {
             .oneshot(
                 axum::http::Request::builder()
                     .method("PUT")
-                    .uri(format!("/circle/{}", circle_id))
+                    .uri(format!("/v1/circle/{}", circle_id))
                     .header(CONTENT_TYPE, "application/json")
                     .body(axum::body::Body::new(serde_json::to_string(
                         &UpdateCircleRequestBody {
@@ -191,7 +191,7 @@ mod tests {
             .oneshot(
                 axum::http::Request::builder()
                     .method("POST")
-                    .uri("/circle")
+                    .uri("/v1/circle")
                     .header(CONTENT_TYPE, "application/json")
                     .body(axum::body::Body::new(serde_json::to_string(
                         &CreateCircleRequestBody {